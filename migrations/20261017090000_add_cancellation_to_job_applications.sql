@@ -0,0 +1,8 @@
+ALTER TABLE job_applications
+ADD COLUMN cancelled_by TEXT,
+ADD COLUMN cancellation_reason_code TEXT,
+ADD COLUMN cancellation_reason TEXT,
+ADD COLUMN cancelled_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX idx_job_applications_status
+ON job_applications (status);
@@ -0,0 +1,13 @@
+CREATE TABLE job_application_status_history (
+    id SERIAL PRIMARY KEY,
+    order_id TEXT NOT NULL,
+    transaction_id TEXT NOT NULL,
+    action TEXT NOT NULL,
+    status TEXT NOT NULL,
+    fulfillment_state_code TEXT,
+    payload JSONB,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_job_application_status_history_order_id
+ON job_application_status_history (order_id, created_at);
@@ -0,0 +1,5 @@
+ALTER TABLE job_applications
+ADD COLUMN last_status_polled_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX idx_job_applications_status_poll
+ON job_applications (bpp_id, last_status_polled_at NULLS FIRST);
@@ -0,0 +1,26 @@
+CREATE TABLE job_apply_sagas (
+    transaction_id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    job_id TEXT NOT NULL,
+    bpp_id TEXT NOT NULL,
+    bpp_uri TEXT NOT NULL,
+    request JSONB NOT NULL,
+    state TEXT NOT NULL,
+    last_message_id TEXT,
+    attempts INT NOT NULL DEFAULT 0,
+    last_error TEXT,
+    order_id TEXT,
+    on_init JSONB,
+    on_confirm JSONB,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    modified_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE TRIGGER trigger_set_modified_at_job_apply_sagas
+BEFORE UPDATE ON job_apply_sagas
+FOR EACH ROW
+EXECUTE PROCEDURE set_modified_at_timestamp();
+
+CREATE INDEX idx_job_apply_sagas_pending
+ON job_apply_sagas (modified_at)
+WHERE state IN ('INIT_SENT', 'INIT_ACKED', 'CONFIRM_SENT');
@@ -0,0 +1,17 @@
+CREATE TABLE beckn_messages (
+    id BIGSERIAL PRIMARY KEY,
+    direction TEXT NOT NULL,
+    action TEXT NOT NULL,
+    transaction_id TEXT NOT NULL,
+    message_id TEXT,
+    bpp_id TEXT,
+    url TEXT,
+    http_status INT,
+    latency_ms BIGINT NOT NULL,
+    error TEXT,
+    payload JSONB,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_beckn_messages_transaction_id
+ON beckn_messages (transaction_id, created_at);
@@ -0,0 +1,7 @@
+ALTER TABLE jobs
+ADD COLUMN embedding_model TEXT,
+ADD COLUMN embedding_dim INT;
+
+CREATE INDEX idx_jobs_embedding_model
+ON jobs (embedding_model, embedding_dim)
+WHERE embedding IS NOT NULL;
@@ -0,0 +1,8 @@
+ALTER TABLE profiles
+ADD COLUMN embedding FLOAT4[],
+ADD COLUMN embedding_model TEXT,
+ADD COLUMN embedding_dim INT;
+
+CREATE INDEX idx_profiles_embedding_null
+ON profiles (id)
+WHERE embedding IS NULL;
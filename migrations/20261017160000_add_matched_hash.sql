@@ -0,0 +1,7 @@
+-- Hash a job/profile had when its vector candidates were last scored; a
+-- different current hash means it needs candidate generation again.
+ALTER TABLE jobs
+ADD COLUMN matched_hash TEXT;
+
+ALTER TABLE profiles
+ADD COLUMN matched_hash TEXT;
@@ -0,0 +1,7 @@
+-- Hash of the match-rule fields that built each vector's embed text.
+-- NULL rows predate it and are stamped with the live rules at startup.
+ALTER TABLE jobs
+ADD COLUMN embedding_rules_hash TEXT;
+
+ALTER TABLE profiles
+ADD COLUMN embedding_rules_hash TEXT;
@@ -1,6 +1,6 @@
 use config::{Config, ConfigError, File};
 use serde::{Deserialize, Serialize};
-use std::{env, process};
+use std::{collections::HashMap, env, process};
 use tracing::error;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -32,6 +32,13 @@ pub struct Bpp {
 pub struct MatchScoreConfig {
     pub config_path: String,
     pub apply_business_logic: bool,
+    // How often config_path is checked for edits
+    #[serde(default = "default_match_score_reload_secs")]
+    pub reload_interval_secs: u64,
+}
+
+fn default_match_score_reload_secs() -> u64 {
+    5
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -49,6 +56,13 @@ pub struct CacheConfig {
     pub result_ttl_secs: u64,
     pub txn_ttl_secs: u64,
     pub throttle_secs: u64,
+    // 0 disables webhook deduplication
+    #[serde(default = "default_webhook_dedupe_secs")]
+    pub webhook_dedupe_secs: u64,
+}
+
+fn default_webhook_dedupe_secs() -> u64 {
+    600
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -60,10 +74,58 @@ pub struct ProfileSchedule {
     pub seconds: u64,
 }
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ApplicationStatusSchedule {
+    pub seconds: u64,
+    // Status calls in flight at once; each BPP gets the fetch_jobs page size per run
+    pub concurrency: usize,
+}
+
+impl Default for ApplicationStatusSchedule {
+    fn default() -> Self {
+        Self {
+            seconds: 1800,
+            concurrency: 8,
+        }
+    }
+}
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ApplySagaSweeperSchedule {
+    pub seconds: u64,
+    pub stale_after_secs: u64,
+    pub max_attempts: i32,
+    pub batch: usize,
+}
+
+impl Default for ApplySagaSweeperSchedule {
+    fn default() -> Self {
+        Self {
+            seconds: 60,
+            stale_after_secs: 120,
+            max_attempts: 3,
+            batch: 50,
+        }
+    }
+}
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct MatchScoreSchedule {
     pub seconds: u64,
     pub batch: usize,
     pub source: String,
+    // Nearest profiles/jobs scored per new job/profile; 0 scores every pair
+    #[serde(default)]
+    pub candidate_top_n: usize,
+    // With candidates, pairs scoring below this (0-10) aren't stored
+    #[serde(default)]
+    pub min_score: i16,
+    // Pair chunks scored and upserted in parallel
+    #[serde(default = "default_match_concurrency")]
+    pub concurrency: usize,
+}
+
+fn default_match_concurrency() -> usize {
+    8
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -87,9 +149,59 @@ pub struct NotificationSchedule {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GcpConfig {
     pub project_id: String,
-    pub model: String,
     pub auth_token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingProvider {
+    Gemini,
+    OpenAi,
+    Local,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EmbeddingConfig {
+    pub provider: EmbeddingProvider,
+    pub model: String,
     pub dimension: u32,
+    // OpenAI-compatible servers only; e.g. "https://api.openai.com" or "http://localhost:11434"
+    #[serde(default)]
+    pub base_url: Option<String>,
+    // Falls back to gcp.auth_token for Gemini
+    #[serde(default)]
+    pub api_key: Option<String>,
+    #[serde(default = "default_embedding_timeout_secs")]
+    pub timeout_secs: u64,
+    // Texts per provider request; capped by what the provider accepts
+    #[serde(default = "default_embedding_batch_size")]
+    pub batch_size: usize,
+    // Provider requests in flight at once
+    #[serde(default = "default_embedding_concurrency")]
+    pub concurrency: usize,
+    // Retries after a 429, with exponential backoff
+    #[serde(default = "default_embedding_max_retries")]
+    pub max_retries: u32,
+    // Model that produced vectors stored before embedding_model was tracked;
+    // unset leaves them untagged so the re-embed worker replaces them
+    #[serde(default)]
+    pub legacy_model: Option<String>,
+}
+
+fn default_embedding_timeout_secs() -> u64 {
+    30
+}
+
+fn default_embedding_batch_size() -> usize {
+    100
+}
+
+fn default_embedding_concurrency() -> usize {
+    4
+}
+
+fn default_embedding_max_retries() -> u32 {
+    5
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -125,18 +237,69 @@ pub struct MetaDataMatch {
     pub penalty: f32,
     #[serde(default)]
     pub bonus: Option<f32>,
+    // Also feed this field into the embedding text when match_mode isn't embed
+    #[serde(default)]
+    pub embed: bool,
+    // Fit (0..1) a value needs to pass; embed fields only get a Jaro-Winkler check when set
+    #[serde(default)]
+    pub cutoff: Option<f32>,
+    #[serde(default)]
+    pub curve: ScoreCurve,
+    // enum: canonical value -> accepted aliases
+    #[serde(default)]
+    pub synonyms: HashMap<String, Vec<String>>,
+    // threshold: how the profile value must compare to the job value
+    #[serde(default)]
+    pub comparison: Option<Comparison>,
+    // geo: distance within which a location fully matches
+    #[serde(default)]
+    pub radius_km: Option<f64>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum MatchMode {
+    // Embedding text, plus optional string similarity against `cutoff`
     Embed,
+    // Profile number within job_path_min..job_path_max
     Manual,
+    // Jaccard overlap of two string sets
+    Overlap,
+    // Exact value match after synonym normalisation
+    Enum,
+    // One-sided numeric bound, see `comparison`
+    Threshold,
+    // Haversine distance between "lat,lon" points against `radius_km`
+    Geo,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Comparison {
+    // profile >= job (e.g. candidate age vs minimum age)
+    Gte,
+    // profile <= job (e.g. salary floor vs offered maximum)
+    Lte,
+}
+
+/// How a field's fit (0..1) becomes a score multiplier.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ScoreCurve {
+    // `bonus` (or 1.0) when fit reaches the cutoff, `penalty` otherwise
+    #[default]
+    Step,
+    // Interpolates from `penalty` at fit 0 to `bonus` (or 1.0) at fit 1
+    Linear,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CronConfig {
     pub fetch_jobs: JobSchedule,
+    #[serde(default)]
+    pub poll_application_status: ApplicationStatusSchedule,
+    #[serde(default)]
+    pub apply_saga_sweeper: ApplySagaSweeperSchedule,
     pub fetch_profiles: ProfileSchedule,
     pub compute_match_scores: MatchScoreSchedule,
     pub notification: NotificationSchedule,
@@ -169,6 +332,185 @@ pub struct ServicesConfig {
 pub struct AuthConfig {
     pub x_api_key: String,
 }
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RegistryMode {
+    #[default]
+    File,
+    Lookup,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RegistryConfig {
+    pub mode: RegistryMode,
+    #[serde(default)]
+    pub file_path: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+    pub cache_ttl_secs: u64,
+}
+
+impl Default for RegistryConfig {
+    fn default() -> Self {
+        Self {
+            mode: RegistryMode::File,
+            file_path: None,
+            url: None,
+            cache_ttl_secs: 3600,
+        }
+    }
+}
+
+// Missing section = signing and verification off, as before it existed
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct SigningConfig {
+    pub enabled: bool,
+    pub unique_key_id: String,
+    pub private_key: String,
+    pub expiry_secs: i64,
+    pub verify_incoming: bool,
+    // Tolerated clock difference on incoming created/expires
+    pub max_clock_skew_secs: i64,
+    pub registry: RegistryConfig,
+}
+
+impl Default for SigningConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            unique_key_id: String::new(),
+            private_key: String::new(),
+            expiry_secs: 300,
+            verify_incoming: false,
+            max_clock_skew_secs: 30,
+            registry: RegistryConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum VectorIndexType {
+    #[default]
+    Flat,
+    Hnsw,
+    IvfFlat,
+    IvfPq,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum VectorMetric {
+    #[default]
+    InnerProduct,
+    L2,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VectorConfig {
+    #[serde(default)]
+    pub index_type: VectorIndexType,
+    #[serde(default)]
+    pub metric: VectorMetric,
+    // Raw FAISS factory string, overrides index_type (e.g. "IDMap,OPQ64,IVF4096,PQ64")
+    #[serde(default)]
+    pub index_factory: Option<String>,
+    #[serde(default = "default_hnsw_m")]
+    pub hnsw_m: u32,
+    #[serde(default = "default_ef_search")]
+    pub ef_search: u32,
+    #[serde(default = "default_nlist")]
+    pub nlist: u32,
+    #[serde(default = "default_nprobe")]
+    pub nprobe: u32,
+    #[serde(default = "default_pq_m")]
+    pub pq_m: u32,
+    #[serde(default = "default_pq_nbits")]
+    pub pq_nbits: u32,
+    // Max vectors sampled from the active jobs to train IVF/PQ indexes
+    #[serde(default = "default_training_sample")]
+    pub training_sample: usize,
+    // Index file; metadata and id map are written next to it
+    #[serde(default = "default_index_path")]
+    pub index_path: String,
+    // Filtered searches start at k * overfetch candidates and double until k pass
+    #[serde(default = "default_overfetch")]
+    pub overfetch: usize,
+}
+
+// Missing section = exact inner-product index, as before it existed
+impl Default for VectorConfig {
+    fn default() -> Self {
+        Self {
+            index_type: VectorIndexType::default(),
+            metric: VectorMetric::default(),
+            index_factory: None,
+            hnsw_m: default_hnsw_m(),
+            ef_search: default_ef_search(),
+            nlist: default_nlist(),
+            nprobe: default_nprobe(),
+            pq_m: default_pq_m(),
+            pq_nbits: default_pq_nbits(),
+            training_sample: default_training_sample(),
+            index_path: default_index_path(),
+            overfetch: default_overfetch(),
+        }
+    }
+}
+
+fn default_hnsw_m() -> u32 {
+    32
+}
+
+fn default_ef_search() -> u32 {
+    64
+}
+
+fn default_nlist() -> u32 {
+    1024
+}
+
+fn default_nprobe() -> u32 {
+    16
+}
+
+fn default_pq_m() -> u32 {
+    64
+}
+
+fn default_pq_nbits() -> u32 {
+    8
+}
+
+fn default_training_sample() -> usize {
+    50_000
+}
+
+fn default_index_path() -> String {
+    "faiss.index".to_string()
+}
+
+fn default_overfetch() -> usize {
+    4
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ContextValidationConfig {
+    pub max_clock_skew_secs: u64,
+    pub enforce_ttl: bool,
+}
+
+impl Default for ContextValidationConfig {
+    fn default() -> Self {
+        Self {
+            max_clock_skew_secs: 300,
+            enforce_ttl: true,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AppConfig {
     pub debug: bool,
@@ -178,11 +520,19 @@ pub struct AppConfig {
     pub db: DbConfig,
     pub cache: CacheConfig,
     pub cron: CronConfig,
-    pub gcp: GcpConfig,
+    #[serde(default)]
+    pub gcp: Option<GcpConfig>,
+    pub embedding: EmbeddingConfig,
+    #[serde(default)]
+    pub vector: VectorConfig,
     pub match_score: MatchScoreConfig,
     pub services: ServicesConfig,
     pub bpp: Bpp,
     pub auth: AuthConfig,
+    #[serde(default)]
+    pub signing: SigningConfig,
+    #[serde(default)]
+    pub context_validation: ContextValidationConfig,
 }
 
 impl AppConfig {
@@ -194,8 +544,23 @@ impl AppConfig {
         }
         let config_path = &args[1];
 
-        let config = Config::builder()
-            .add_source(File::with_name(&config_path))
+        let raw = Config::builder()
+            .add_source(File::with_name(config_path))
+            .build()?;
+
+        // Configs from before the embedding section kept the model under gcp.
+        let mut builder = Config::builder();
+        if let (Ok(model), Ok(dimension)) =
+            (raw.get_string("gcp.model"), raw.get_int("gcp.dimension"))
+        {
+            builder = builder
+                .set_default("embedding.provider", "gemini")?
+                .set_default("embedding.model", model)?
+                .set_default("embedding.dimension", dimension)?;
+        }
+
+        let config = builder
+            .add_source(File::with_name(config_path))
             .build()?
             .try_deserialize()?;
 
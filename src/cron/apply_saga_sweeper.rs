@@ -0,0 +1,98 @@
+use crate::db::job_apply_sagas::{
+    claim_step, get_stale_sagas, mark_failed, JobApplySaga, SAGA_CONFIRM_SENT, SAGA_INIT_ACKED,
+    SAGA_INIT_SENT,
+};
+use crate::models::job_apply::JobApplyRequest;
+use crate::services::apply_saga::send_apply_action;
+use crate::state::AppState;
+use std::sync::Arc;
+use tracing::{error, info};
+use uuid::Uuid;
+
+pub async fn run(app_state: Arc<AppState>) {
+    let config = &app_state.config.cron.apply_saga_sweeper;
+
+    let sagas = match get_stale_sagas(
+        &app_state.db_pool,
+        config.stale_after_secs as i64,
+        config.batch as i64,
+    )
+    .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            error!(target: "cron", "❌ Failed to load stale apply sagas: {:?}", e);
+            return;
+        }
+    };
+
+    if sagas.is_empty() {
+        return;
+    }
+
+    info!(target: "cron", "🧹 Sweeping {} stuck apply sagas", sagas.len());
+
+    for saga in sagas {
+        sweep_saga(&app_state, saga, config.max_attempts).await;
+    }
+}
+
+async fn sweep_saga(app_state: &AppState, saga: JobApplySaga, max_attempts: i32) {
+    let txn_id = saga.transaction_id.as_str();
+
+    let req: JobApplyRequest = match serde_json::from_value(saga.request.clone()) {
+        Ok(r) => r,
+        Err(e) => {
+            error!(target: "cron", "❌ Apply saga {} has an unreadable request: {:?}", txn_id, e);
+            let _ = mark_failed(&app_state.db_pool, txn_id, "Stored request is invalid").await;
+            return;
+        }
+    };
+
+    let (action, next_state) = match saga.state.as_str() {
+        SAGA_INIT_SENT => ("init", SAGA_INIT_SENT),
+        SAGA_INIT_ACKED => ("confirm", SAGA_CONFIRM_SENT),
+        SAGA_CONFIRM_SENT => ("confirm", SAGA_CONFIRM_SENT),
+        _ => return,
+    };
+
+    // INIT_ACKED hasn't sent confirm yet, so it never counts as a retry.
+    if saga.state == next_state && saga.attempts >= max_attempts {
+        let reason = format!("No on_{} after {} attempts", action, saga.attempts);
+        info!(target: "cron", "⌛ Expiring apply saga {}: {}", txn_id, reason);
+        if let Err(e) = mark_failed(&app_state.db_pool, txn_id, &reason).await {
+            error!(target: "cron", "❌ Failed to expire apply saga {}: {:?}", txn_id, e);
+        }
+        return;
+    }
+
+    let message_id = format!("msg-{}", Uuid::new_v4());
+
+    match claim_step(
+        &app_state.db_pool,
+        txn_id,
+        &saga.state,
+        next_state,
+        &message_id,
+        Some(saga.modified_at),
+    )
+    .await
+    {
+        Ok(true) => {}
+        // Someone else (a late callback or another replica) moved it on.
+        Ok(false) => return,
+        Err(e) => {
+            error!(target: "cron", "❌ Failed to claim apply saga {}: {:?}", txn_id, e);
+            return;
+        }
+    }
+
+    match send_apply_action(app_state, &req, action, &message_id).await {
+        Ok(()) => info!(target: "cron", "🔁 Re-sent {} for apply saga {}", action, txn_id),
+        Err(e) => error!(
+            target: "cron",
+            "❌ Failed to re-send {} for apply saga {}: {}",
+            action, txn_id, e
+        ),
+    }
+}
@@ -1,5 +1,5 @@
 use crate::state::AppState;
-use crate::utils::search::send_open_jobs_search;
+use crate::utils::search::{send_open_jobs_search, OPEN_JOBS_PAGE_SIZE};
 use std::sync::Arc;
 use tracing::info;
 
@@ -8,5 +8,5 @@ pub async fn run(app_state: Arc<AppState>) {
     info!(target: "cron", "║   🔄 Starting fetch jobs cron.             ║");
     info!(target: "cron", "╚════════════════════════════════════════════╝");
 
-    send_open_jobs_search(&app_state, 1, 30, "cron", None, None, None).await;
+    send_open_jobs_search(&app_state, 1, OPEN_JOBS_PAGE_SIZE, "cron", None, None, None).await;
 }
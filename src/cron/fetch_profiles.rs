@@ -2,6 +2,7 @@ use crate::cron::job_profile_match;
 use crate::db::profiles::{delete_stale_profiles, store_profiles, NewProfile};
 use crate::state::AppState;
 use crate::utils::http_client::get_json;
+use crate::utils::profiles::update_profile_embeddings;
 use chrono::{DateTime, Utc};
 use reqwest::header;
 use serde::Deserialize;
@@ -164,6 +165,8 @@ pub async fn run(app_state: Arc<AppState>) {
         sleep(Duration::from_millis(300)).await;
     }
 
+    update_profile_embeddings(&app_state).await;
+
     if sync_completed {
         match delete_stale_profiles(&app_state.db_pool, sync_started_at).await {
             Ok(count) => {
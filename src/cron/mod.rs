@@ -3,11 +3,13 @@ use crate::utils::cron::{build_cron_expr, build_notification_cron_expr};
 use std::sync::Arc;
 use tokio::time::{sleep, Duration};
 use tokio_cron_scheduler::{Job, JobScheduler};
+mod apply_saga_sweeper;
 mod fetch_jobs;
 pub mod fetch_profiles;
 
 pub mod job_profile_match;
 mod notification;
+mod poll_application_status;
 pub async fn start_cron_jobs(state: Arc<AppState>) -> JobScheduler {
     let scheduler = JobScheduler::new().await.unwrap();
 
@@ -70,6 +72,67 @@ pub async fn start_cron_jobs(state: Arc<AppState>) -> JobScheduler {
         .await
         .unwrap();
 
+    /*
+     * ------------------------------------------------------------
+     * poll_application_status cron
+     * ------------------------------------------------------------
+     */
+
+    let (status_desc, status_cron_expr) =
+        build_cron_expr(state.config.cron.poll_application_status.seconds);
+
+    tracing::info!(
+        "📅 Scheduling poll_application_status cron: {} → {}",
+        status_desc,
+        status_cron_expr
+    );
+
+    scheduler
+        .add(
+            Job::new_async(&status_cron_expr, {
+                let state = state.clone();
+                move |_uuid, _l| {
+                    let state = state.clone();
+                    Box::pin(async move {
+                        poll_application_status::run(state).await;
+                    })
+                }
+            })
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    /*
+     * ------------------------------------------------------------
+     * apply_saga_sweeper cron
+     * ------------------------------------------------------------
+     */
+
+    let (saga_desc, saga_cron_expr) = build_cron_expr(state.config.cron.apply_saga_sweeper.seconds);
+
+    tracing::info!(
+        "📅 Scheduling apply_saga_sweeper cron: {} → {}",
+        saga_desc,
+        saga_cron_expr
+    );
+
+    scheduler
+        .add(
+            Job::new_async(&saga_cron_expr, {
+                let state = state.clone();
+                move |_uuid, _l| {
+                    let state = state.clone();
+                    Box::pin(async move {
+                        apply_saga_sweeper::run(state).await;
+                    })
+                }
+            })
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+
     /*
      * ------------------------------------------------------------
      * fetch_profiles cron
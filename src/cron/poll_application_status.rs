@@ -0,0 +1,109 @@
+use crate::db::job_applications::{
+    get_applications_for_status_poll, mark_status_polled, PollableApplication,
+};
+use crate::models::status::{StatusMessage, StatusOrder};
+use crate::services::payload_generator::build_beckn_payload;
+use crate::state::AppState;
+use crate::utils::http_client::post_beckn_json;
+use crate::utils::search::OPEN_JOBS_PAGE_SIZE;
+use futures::stream::{self, StreamExt};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{error, info};
+use uuid::Uuid;
+
+/// Sends fire-and-forget `status` calls for open applications; the async
+/// `on_status` callbacks update the rows through the webhook.
+pub async fn run(app_state: Arc<AppState>) {
+    info!(target: "cron", "╔════════════════════════════════════════════╗");
+    info!(target: "cron", "║   🔄 Starting application status poll.     ║");
+    info!(target: "cron", "╚════════════════════════════════════════════╝");
+
+    let per_bpp_limit = OPEN_JOBS_PAGE_SIZE as i64;
+    let concurrency = app_state.config.cron.poll_application_status.concurrency;
+
+    let applications =
+        match get_applications_for_status_poll(&app_state.db_pool, per_bpp_limit).await {
+            Ok(rows) => rows,
+            Err(e) => {
+                error!(target: "cron", "❌ Failed to load open applications: {:?}", e);
+                return;
+            }
+        };
+
+    if applications.is_empty() {
+        info!(target: "cron", "✅ No open applications to poll");
+        return;
+    }
+
+    let adapter_url = format!("{}/status", app_state.config.bap.caller_uri);
+
+    let results: Vec<(PollableApplication, bool)> = stream::iter(applications)
+        .map(|app| {
+            let app_state = &app_state;
+            let adapter_url = &adapter_url;
+            async move {
+                let sent = send_status(app_state, adapter_url, &app).await;
+                (app, sent)
+            }
+        })
+        .buffer_unordered(concurrency.max(1))
+        .collect()
+        .await;
+
+    let mut by_bpp: HashMap<&str, (usize, usize)> = HashMap::new();
+    let mut polled = Vec::with_capacity(results.len());
+    for (app, sent) in &results {
+        let (ok, total) = by_bpp.entry(app.bpp_id.as_str()).or_default();
+        *total += 1;
+        if *sent {
+            *ok += 1;
+            polled.push(app.order_id.clone());
+        }
+    }
+
+    for (bpp_id, (ok, total)) in by_bpp {
+        info!(
+            target: "cron",
+            "📨 Sent {}/{} status calls to {}",
+            ok,
+            total,
+            bpp_id
+        );
+    }
+
+    if let Err(e) = mark_status_polled(&app_state.db_pool, &polled).await {
+        error!(target: "cron", "❌ Failed to mark applications polled: {:?}", e);
+    }
+}
+
+async fn send_status(app_state: &AppState, adapter_url: &str, app: &PollableApplication) -> bool {
+    let message = StatusMessage {
+        order: StatusOrder {
+            id: app.order_id.clone(),
+        },
+    };
+    let message_id = format!("msg-{}", Uuid::new_v4());
+
+    let payload = build_beckn_payload(
+        &app_state.config,
+        &app.transaction_id,
+        &message_id,
+        &message,
+        "status",
+        Some(&app.bpp_id),
+        Some(&app.bpp_uri),
+    );
+
+    match post_beckn_json(app_state, adapter_url, payload).await {
+        Ok(_) => true,
+        Err(e) => {
+            error!(
+                target: "cron",
+                "❌ Failed to send status for order {} (bpp_id={}): {}",
+                app.order_id, app.bpp_id, e
+            );
+            false
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{query, query_as, Error, FromRow, PgPool};
+
+#[derive(Debug)]
+pub struct NewBecknMessage {
+    pub direction: &'static str,
+    pub action: String,
+    pub transaction_id: String,
+    pub message_id: Option<String>,
+    pub bpp_id: Option<String>,
+    pub url: Option<String>,
+    pub http_status: Option<i32>,
+    pub latency_ms: i64,
+    pub error: Option<String>,
+    pub payload: Option<Value>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct BecknMessage {
+    pub id: i64,
+    pub direction: String,
+    pub action: String,
+    pub transaction_id: String,
+    pub message_id: Option<String>,
+    pub bpp_id: Option<String>,
+    pub url: Option<String>,
+    pub http_status: Option<i32>,
+    pub latency_ms: i64,
+    pub error: Option<String>,
+    pub payload: Option<Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+pub async fn store_beckn_message(db_pool: &PgPool, data: NewBecknMessage) -> Result<(), Error> {
+    query(
+        r#"
+        INSERT INTO beckn_messages
+            (direction, action, transaction_id, message_id, bpp_id, url,
+             http_status, latency_ms, error, payload)
+        VALUES
+            ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        "#,
+    )
+    .bind(data.direction)
+    .bind(data.action)
+    .bind(data.transaction_id)
+    .bind(data.message_id)
+    .bind(data.bpp_id)
+    .bind(data.url)
+    .bind(data.http_status)
+    .bind(data.latency_ms)
+    .bind(data.error)
+    .bind(data.payload)
+    .execute(db_pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_beckn_messages_by_txn(
+    db_pool: &PgPool,
+    transaction_id: &str,
+) -> Result<Vec<BecknMessage>, Error> {
+    query_as::<_, BecknMessage>(
+        r#"
+        SELECT id, direction, action, transaction_id, message_id, bpp_id, url,
+               http_status, latency_ms, error, payload, created_at
+        FROM beckn_messages
+        WHERE transaction_id = $1
+        ORDER BY created_at ASC, id ASC
+        "#,
+    )
+    .bind(transaction_id)
+    .fetch_all(db_pool)
+    .await
+}
@@ -129,6 +129,21 @@ pub async fn store_jobs(db_pool: &PgPool, jobs: &[NewJob]) -> Result<(), Error>
                 THEN NULL
                 ELSE jobs.embedding
             END,
+            embedding_model = CASE
+                WHEN jobs.hash IS DISTINCT FROM EXCLUDED.hash
+                THEN NULL
+                ELSE jobs.embedding_model
+            END,
+            embedding_dim = CASE
+                WHEN jobs.hash IS DISTINCT FROM EXCLUDED.hash
+                THEN NULL
+                ELSE jobs.embedding_dim
+            END,
+            embedding_rules_hash = CASE
+                WHEN jobs.hash IS DISTINCT FROM EXCLUDED.hash
+                THEN NULL
+                ELSE jobs.embedding_rules_hash
+            END,
             transaction_id = EXCLUDED.transaction_id,
             bpp_id = EXCLUDED.bpp_id,
             bpp_uri = EXCLUDED.bpp_uri,
@@ -238,17 +253,26 @@ pub async fn fetch_jobs_pending_embedding(
 pub async fn batch_update_job_embeddings(
     db_pool: &PgPool,
     updates: &[(uuid::Uuid, Vec<f32>)],
+    model: &str,
+    dimension: i32,
+    rules_hash: &str,
 ) -> Result<(), sqlx::Error> {
     for (id, embedding) in updates {
         sqlx::query(
             r#"
             UPDATE jobs
-            SET embedding = $1
+            SET embedding = $1,
+                embedding_model = $3,
+                embedding_dim = $4,
+                embedding_rules_hash = $5
             WHERE id = $2
             "#,
         )
         .bind(embedding)
         .bind(id)
+        .bind(model)
+        .bind(dimension)
+        .bind(rules_hash)
         .execute(db_pool)
         .await?;
     }
@@ -256,6 +280,214 @@ pub async fn batch_update_job_embeddings(
     Ok(())
 }
 
+/// Stamps vectors written before versioning with the model that produced them,
+/// as named by the operator, and their actual length.
+pub async fn adopt_unversioned_embeddings(
+    db_pool: &PgPool,
+    legacy_model: &str,
+) -> Result<u64, sqlx::Error> {
+    let result = query(
+        r#"
+        UPDATE jobs
+        SET embedding_model = $1,
+            embedding_dim = array_length(embedding, 1)
+        WHERE embedding IS NOT NULL
+          AND embedding_model IS NULL
+        "#,
+    )
+    .bind(legacy_model)
+    .execute(db_pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Stamps job vectors stored before rules hashing with the live rules.
+pub async fn adopt_unhashed_job_embeddings(
+    db_pool: &PgPool,
+    rules_hash: &str,
+) -> Result<u64, sqlx::Error> {
+    let result = query(
+        r#"
+        UPDATE jobs
+        SET embedding_rules_hash = $1
+        WHERE embedding IS NOT NULL
+          AND embedding_rules_hash IS NULL
+        "#,
+    )
+    .bind(rules_hash)
+    .execute(db_pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+pub struct EmbeddingCounts {
+    // Usable by the index: right model and dimension
+    pub current: i64,
+    // Due for re-embedding: wrong model or dimension, or built from older embed rules
+    pub stale: i64,
+}
+
+pub async fn count_embeddings_by_model(
+    db_pool: &PgPool,
+    model: &str,
+    dimension: i32,
+    rules_hash: &str,
+) -> Result<EmbeddingCounts, sqlx::Error> {
+    let row = query(
+        r#"
+        SELECT
+            COUNT(*) FILTER (
+                WHERE embedding_model = $1 AND embedding_dim = $2
+            ) AS current,
+            COUNT(*) FILTER (
+                WHERE embedding_model IS DISTINCT FROM $1
+                   OR embedding_dim IS DISTINCT FROM $2
+                   OR embedding_rules_hash IS DISTINCT FROM $3
+            ) AS stale
+        FROM jobs
+        WHERE embedding IS NOT NULL
+        "#,
+    )
+    .bind(model)
+    .bind(dimension)
+    .bind(rules_hash)
+    .fetch_one(db_pool)
+    .await?;
+
+    Ok(EmbeddingCounts {
+        current: row.get("current"),
+        stale: row.get("stale"),
+    })
+}
+
+/// Models other than the current one that still own vectors, for cache cleanup.
+pub async fn fetch_stale_embedding_models(
+    db_pool: &PgPool,
+    model: &str,
+) -> Result<Vec<String>, sqlx::Error> {
+    let rows = query(
+        r#"
+        SELECT DISTINCT embedding_model
+        FROM jobs
+        WHERE embedding IS NOT NULL
+          AND embedding_model IS NOT NULL
+          AND embedding_model <> $1
+        "#,
+    )
+    .bind(model)
+    .fetch_all(db_pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| row.get::<String, _>("embedding_model"))
+        .collect())
+}
+
+/// Next page of jobs whose vector came from another model, dimension or set of
+/// embed rules, ordered by id.
+pub async fn fetch_jobs_with_stale_embeddings(
+    db_pool: &PgPool,
+    model: &str,
+    dimension: i32,
+    rules_hash: &str,
+    after: Option<Uuid>,
+    limit: i64,
+) -> Result<Vec<JobRow>, sqlx::Error> {
+    query_as::<_, JobRow>(
+        r#"
+        SELECT
+            id,
+            hash,
+            metadata,
+            beckn_structure,
+            job_id,
+            bpp_id,
+            embedding
+        FROM jobs
+        WHERE embedding IS NOT NULL
+          AND (
+            embedding_model IS DISTINCT FROM $1
+            OR embedding_dim IS DISTINCT FROM $2
+            OR embedding_rules_hash IS DISTINCT FROM $3
+          )
+          AND ($4::uuid IS NULL OR id > $4)
+        ORDER BY id
+        LIMIT $5
+        "#,
+    )
+    .bind(model)
+    .bind(dimension)
+    .bind(rules_hash)
+    .bind(after)
+    .bind(limit)
+    .fetch_all(db_pool)
+    .await
+}
+
+/// Postgres-side predicates for narrowing vector search results.
+#[derive(Debug, Default)]
+pub struct JobSearchFilter {
+    pub bpp_id: Option<String>,
+    pub exclude_provider_id: Option<String>,
+    pub role: Option<String>,
+    pub industry: Option<String>,
+    pub location: Option<String>,
+}
+
+impl JobSearchFilter {
+    pub fn is_empty(&self) -> bool {
+        self.bpp_id.is_none()
+            && self.exclude_provider_id.is_none()
+            && self.role.is_none()
+            && self.industry.is_none()
+            && self.location.is_none()
+    }
+}
+
+/// Ids of active, embedded jobs matching `filter`; empty fields don't restrict.
+pub async fn fetch_filtered_job_ids(
+    pool: &PgPool,
+    filter: &JobSearchFilter,
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    sqlx::query_scalar::<_, Uuid>(
+        r#"
+        SELECT id
+        FROM jobs
+        WHERE is_active = true
+          AND embedding IS NOT NULL
+          AND ($1::text IS NULL OR bpp_id = $1)
+          AND ($5::text IS NULL OR provider_id <> $5)
+          AND ($2::text IS NULL
+               OR COALESCE(beckn_structure #>> '{tags,role}', '') ILIKE '%' || trim($2) || '%')
+          AND ($3::text IS NULL
+               OR COALESCE(beckn_structure #>> '{tags,industry}', '') ILIKE '%' || trim($3) || '%')
+          AND ($4::text IS NULL
+               OR COALESCE((beckn_structure #> '{tags,jobProviderLocation}')::text, '')
+                    ILIKE '%' || trim($4) || '%'
+               OR EXISTS (
+                    SELECT 1
+                    FROM jsonb_array_elements(
+                        CASE WHEN jsonb_typeof(beckn_structure -> 'locations') = 'array'
+                             THEN beckn_structure -> 'locations'
+                             ELSE '[]'::jsonb
+                        END
+                    ) loc
+                    WHERE loc::text ILIKE '%' || trim($4) || '%'
+               ))
+        "#,
+    )
+    .bind(filter.bpp_id.as_deref())
+    .bind(filter.role.as_deref())
+    .bind(filter.industry.as_deref())
+    .bind(filter.location.as_deref())
+    .bind(filter.exclude_provider_id.as_deref())
+    .fetch_all(pool)
+    .await
+}
+
 pub async fn fetch_jobs_by_ids(
     pool: &PgPool,
     job_ids: &[Uuid],
@@ -284,16 +516,183 @@ pub async fn fetch_jobs_by_ids(
 
     Ok(jobs)
 }
-pub fn stream_active_jobs_with_embeddings(
-    db_pool: &PgPool,
-) -> BoxStream<'_, Result<JobEmbeddingRow, sqlx::Error>> {
+
+/// Like `fetch_jobs_by_ids`, but drops jobs that have since been deactivated.
+pub async fn fetch_active_jobs_by_ids(
+    pool: &PgPool,
+    job_ids: &[Uuid],
+) -> Result<Vec<JobRow>, sqlx::Error> {
+    if job_ids.is_empty() {
+        return Ok(vec![]);
+    }
+
+    query_as::<_, JobRow>(
+        r#"
+        SELECT
+            id,
+            hash,
+            metadata,
+            beckn_structure,
+            job_id,
+            bpp_id,
+            embedding
+        FROM jobs
+        WHERE id = ANY($1)
+          AND is_active = true
+        "#,
+    )
+    .bind(job_ids)
+    .fetch_all(pool)
+    .await
+}
+
+pub fn stream_active_jobs_with_embeddings<'a>(
+    db_pool: &'a PgPool,
+    model: &'a str,
+    dimension: i32,
+) -> BoxStream<'a, Result<JobEmbeddingRow, sqlx::Error>> {
     sqlx::query_as::<_, JobEmbeddingRow>(
         r#"
         SELECT id, embedding
         FROM jobs
         WHERE is_active = true
           AND embedding IS NOT NULL
+          AND embedding_model = $1
+          AND embedding_dim = $2
         "#,
     )
+    .bind(model)
+    .bind(dimension)
     .fetch(db_pool)
 }
+
+/// Active jobs whose vector is from the current model and sized for the index.
+pub async fn fetch_indexable_job_ids(
+    db_pool: &PgPool,
+    model: &str,
+    dimension: i32,
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    sqlx::query_scalar::<_, Uuid>(
+        r#"
+        SELECT id
+        FROM jobs
+        WHERE is_active = true
+          AND embedding IS NOT NULL
+          AND embedding_model = $1
+          AND embedding_dim = $2
+          AND cardinality(embedding) = $2
+        "#,
+    )
+    .bind(model)
+    .bind(dimension)
+    .fetch_all(db_pool)
+    .await
+}
+
+/// Active jobs whose stored vector length doesn't match `dimension`.
+pub async fn fetch_job_ids_with_dimension_mismatch(
+    db_pool: &PgPool,
+    dimension: i32,
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    sqlx::query_scalar::<_, Uuid>(
+        r#"
+        SELECT id
+        FROM jobs
+        WHERE is_active = true
+          AND embedding IS NOT NULL
+          AND cardinality(embedding) <> $1
+        "#,
+    )
+    .bind(dimension)
+    .fetch_all(db_pool)
+    .await
+}
+
+pub async fn fetch_job_embeddings_by_ids(
+    db_pool: &PgPool,
+    job_ids: &[Uuid],
+) -> Result<Vec<JobEmbeddingRow>, sqlx::Error> {
+    if job_ids.is_empty() {
+        return Ok(vec![]);
+    }
+
+    query_as::<_, JobEmbeddingRow>(
+        r#"
+        SELECT id, embedding
+        FROM jobs
+        WHERE id = ANY($1)
+          AND embedding IS NOT NULL
+        "#,
+    )
+    .bind(job_ids)
+    .fetch_all(db_pool)
+    .await
+}
+
+#[derive(Debug, FromRow)]
+pub struct SimilarJobRow {
+    pub id: Uuid,
+    pub job_id: String,
+    pub provider_id: String,
+    pub bpp_id: String,
+    pub beckn_structure: Option<Value>,
+    pub embedding: Option<Vec<f32>>,
+    pub embedding_model: Option<String>,
+    pub embedding_dim: Option<i32>,
+}
+
+impl SimilarJobRow {
+    /// Stored vector, if it was produced by the given model and dimension.
+    pub fn current_embedding(&self, model: &str, dimension: usize) -> Option<&[f32]> {
+        let embedding = self.embedding.as_deref()?;
+        (self.embedding_model.as_deref() == Some(model)
+            && self.embedding_dim == Some(dimension as i32)
+            && embedding.len() == dimension)
+            .then_some(embedding)
+    }
+}
+
+/// Active job by its Beckn id, with the vector to search from.
+pub async fn fetch_similarity_source(
+    db_pool: &PgPool,
+    job_id: &str,
+) -> Result<Option<SimilarJobRow>, sqlx::Error> {
+    query_as::<_, SimilarJobRow>(
+        r#"
+        SELECT id, job_id, provider_id, bpp_id, beckn_structure, embedding,
+               embedding_model, embedding_dim
+        FROM jobs
+        WHERE job_id = $1
+          AND is_active = true
+        ORDER BY embedding IS NULL, updated_at DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(job_id)
+    .fetch_optional(db_pool)
+    .await
+}
+
+pub async fn fetch_similar_jobs_by_ids(
+    db_pool: &PgPool,
+    ids: &[Uuid],
+) -> Result<Vec<SimilarJobRow>, sqlx::Error> {
+    if ids.is_empty() {
+        return Ok(vec![]);
+    }
+
+    query_as::<_, SimilarJobRow>(
+        r#"
+        SELECT id, job_id, provider_id, bpp_id, beckn_structure,
+               NULL::float4[] AS embedding,
+               NULL::text AS embedding_model,
+               NULL::int4 AS embedding_dim
+        FROM jobs
+        WHERE id = ANY($1)
+          AND is_active = true
+        "#,
+    )
+    .bind(ids)
+    .fetch_all(db_pool)
+    .await
+}
@@ -0,0 +1,102 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{query, query_as, Error, FromRow, PgExecutor, PgPool};
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct StatusHistoryEntry {
+    pub status: String,
+    pub fulfillment_state_code: Option<String>,
+    pub action: String,
+    pub created_at: DateTime<Utc>,
+}
+
+pub struct NewStatusTransition<'a> {
+    pub order_id: &'a str,
+    pub transaction_id: &'a str,
+    pub action: &'a str,
+    pub status: &'a str,
+    pub fulfillment_state_code: Option<&'a str>,
+    pub payload: Option<&'a Value>,
+}
+
+/// Appends a transition unless it repeats the latest recorded state for the order,
+/// so repeated on_status polls don't flood the timeline.
+/// Returns true when a new row was written.
+pub async fn record_status_transition(
+    db: impl PgExecutor<'_>,
+    data: NewStatusTransition<'_>,
+) -> Result<bool, Error> {
+    let result = query(
+        r#"
+        INSERT INTO job_application_status_history
+            (order_id, transaction_id, action, status, fulfillment_state_code, payload)
+        SELECT $1, $2, $3, $4, $5, $6
+        WHERE NOT EXISTS (
+            SELECT 1 FROM (
+                SELECT status, fulfillment_state_code
+                FROM job_application_status_history
+                WHERE order_id = $1
+                ORDER BY created_at DESC, id DESC
+                LIMIT 1
+            ) latest
+            WHERE latest.status = $4
+              AND latest.fulfillment_state_code IS NOT DISTINCT FROM $5
+        )
+        "#,
+    )
+    .bind(data.order_id)
+    .bind(data.transaction_id)
+    .bind(data.action)
+    .bind(data.status)
+    .bind(data.fulfillment_state_code)
+    .bind(data.payload)
+    .execute(db)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Moves an application held by `bpp_id` to `status` (or keeps its status when
+/// `None`) unless it already reached a terminal state, so late, out-of-order or
+/// foreign callbacks can't revive it. Returns false when no application matched.
+pub async fn update_job_application_status(
+    db: impl PgExecutor<'_>,
+    order_id: &str,
+    bpp_id: Option<&str>,
+    status: Option<&str>,
+) -> Result<bool, Error> {
+    let result = query(
+        r#"
+        UPDATE job_applications
+        SET status = COALESCE($3, status)
+        WHERE order_id = $1
+          AND bpp_id = $2
+          AND UPPER(COALESCE(status, '')) NOT IN ('CANCELLED', 'REJECTED', 'HIRED')
+        "#,
+    )
+    .bind(order_id)
+    .bind(bpp_id)
+    .bind(status)
+    .execute(db)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn get_status_history(
+    db_pool: &PgPool,
+    order_id: &str,
+) -> Result<Vec<StatusHistoryEntry>, Error> {
+    query_as::<_, StatusHistoryEntry>(
+        r#"
+        SELECT status, fulfillment_state_code, action, created_at
+        FROM job_application_status_history
+        WHERE order_id = $1
+        ORDER BY created_at ASC, id ASC
+        "#,
+    )
+    .bind(order_id)
+    .fetch_all(db_pool)
+    .await
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use sqlx::{query, Error, FromRow, PgPool, Postgres, QueryBuilder};
+use sqlx::{query, Error, FromRow, PgExecutor, PgPool, Postgres, QueryBuilder};
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct NewJobApplication {
@@ -15,7 +15,7 @@ pub struct NewJobApplication {
 }
 
 pub async fn store_job_applications(
-    db_pool: &PgPool,
+    db: impl PgExecutor<'_>,
     data: NewJobApplication,
 ) -> Result<(), Error> {
     query(
@@ -34,7 +34,7 @@ pub async fn store_job_applications(
     .bind(&data.bpp_uri)
     .bind(data.status.unwrap_or_else(|| "pending".to_string()))
     .bind(data.metadata)
-    .execute(db_pool)
+    .execute(db)
     .await?;
 
     Ok(())
@@ -81,3 +81,166 @@ pub async fn get_job_applications(
     let results = query.fetch_all(db_pool).await?;
     Ok(results)
 }
+
+pub async fn get_job_application_by_order_id(
+    db_pool: &PgPool,
+    order_id: &str,
+) -> Result<Option<NewJobApplication>, Error> {
+    sqlx::query_as::<_, NewJobApplication>(
+        r#"
+        SELECT user_id, job_id, order_id, transaction_id, bpp_id, bpp_uri, status, metadata
+        FROM job_applications
+        WHERE order_id = $1
+        "#,
+    )
+    .bind(order_id)
+    .fetch_optional(db_pool)
+    .await
+}
+
+/// Stores who asked for a cancellation and why; the status only flips once
+/// the BPP confirms it in on_cancel. Cleared again by `clear_cancel_request`
+/// if the cancel is rejected or never answered.
+pub async fn record_cancel_request(
+    db_pool: &PgPool,
+    order_id: &str,
+    cancelled_by: &str,
+    reason_code: &str,
+    reason: Option<&str>,
+) -> Result<(), Error> {
+    query(
+        r#"
+        UPDATE job_applications
+        SET cancelled_by = $2,
+            cancellation_reason_code = $3,
+            cancellation_reason = $4
+        WHERE order_id = $1
+          AND UPPER(COALESCE(status, '')) <> 'CANCELLED'
+        "#,
+    )
+    .bind(order_id)
+    .bind(cancelled_by)
+    .bind(reason_code)
+    .bind(reason)
+    .execute(db_pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Drops the details of a pending cancel request that the BPP rejected or
+/// never answered. Scoped like `mark_job_application_cancelled`.
+pub async fn clear_cancel_request(
+    db_pool: &PgPool,
+    order_id: &str,
+    bpp_id: Option<&str>,
+    transaction_id: &str,
+) -> Result<(), Error> {
+    query(
+        r#"
+        UPDATE job_applications
+        SET cancelled_by = NULL,
+            cancellation_reason_code = NULL,
+            cancellation_reason = NULL
+        WHERE order_id = $1
+          AND bpp_id = $2
+          AND transaction_id = $3
+          AND UPPER(COALESCE(status, '')) <> 'CANCELLED'
+        "#,
+    )
+    .bind(order_id)
+    .bind(bpp_id)
+    .bind(transaction_id)
+    .execute(db_pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Marks a confirmed cancellation. The callback's details win, falling back
+/// to those stored by the request. Only the BPP holding the order can cancel
+/// it, and only within the order's transaction. Returns false when no row
+/// matched or it was already cancelled.
+pub async fn mark_job_application_cancelled(
+    db_pool: &PgPool,
+    order_id: &str,
+    bpp_id: Option<&str>,
+    transaction_id: &str,
+    cancelled_by: Option<&str>,
+    reason_code: Option<&str>,
+    reason: Option<&str>,
+) -> Result<bool, Error> {
+    let result = query(
+        r#"
+        UPDATE job_applications
+        SET status = 'CANCELLED',
+            cancelled_by = COALESCE($2, cancelled_by),
+            cancellation_reason_code = COALESCE($3, cancellation_reason_code),
+            cancellation_reason = COALESCE($4, cancellation_reason),
+            cancelled_at = now()
+        WHERE order_id = $1
+          AND bpp_id = $5
+          AND transaction_id = $6
+          AND UPPER(COALESCE(status, '')) <> 'CANCELLED'
+        "#,
+    )
+    .bind(order_id)
+    .bind(cancelled_by)
+    .bind(reason_code)
+    .bind(reason)
+    .bind(bpp_id)
+    .bind(transaction_id)
+    .execute(db_pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+#[derive(Debug, FromRow)]
+pub struct PollableApplication {
+    pub order_id: String,
+    pub transaction_id: String,
+    pub bpp_id: String,
+    pub bpp_uri: String,
+}
+
+/// Non-terminal applications, least recently polled first, capped per BPP.
+pub async fn get_applications_for_status_poll(
+    db_pool: &PgPool,
+    per_bpp_limit: i64,
+) -> Result<Vec<PollableApplication>, Error> {
+    sqlx::query_as::<_, PollableApplication>(
+        r#"
+        SELECT order_id, transaction_id, bpp_id, bpp_uri
+        FROM (
+            SELECT order_id, transaction_id, bpp_id, bpp_uri,
+                   ROW_NUMBER() OVER (
+                       PARTITION BY bpp_id
+                       ORDER BY last_status_polled_at ASC NULLS FIRST, id ASC
+                   ) AS rn
+            FROM job_applications
+            WHERE UPPER(status) NOT IN ('CANCELLED', 'REJECTED', 'HIRED')
+        ) ranked
+        WHERE rn <= $1
+        ORDER BY bpp_id
+        "#,
+    )
+    .bind(per_bpp_limit)
+    .fetch_all(db_pool)
+    .await
+}
+
+pub async fn mark_status_polled(db_pool: &PgPool, order_ids: &[String]) -> Result<(), Error> {
+    query(
+        r#"
+        UPDATE job_applications
+        SET last_status_polled_at = now()
+        WHERE order_id = ANY($1)
+        "#,
+    )
+    .bind(order_ids)
+    .execute(db_pool)
+    .await?;
+
+    Ok(())
+}
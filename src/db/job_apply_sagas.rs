@@ -0,0 +1,209 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{query, query_as, Error, FromRow, PgExecutor, PgPool};
+
+pub const SAGA_INIT_SENT: &str = "INIT_SENT";
+pub const SAGA_INIT_ACKED: &str = "INIT_ACKED";
+pub const SAGA_CONFIRM_SENT: &str = "CONFIRM_SENT";
+pub const SAGA_CONFIRMED: &str = "CONFIRMED";
+pub const SAGA_FAILED: &str = "FAILED";
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct JobApplySaga {
+    pub transaction_id: String,
+    pub user_id: String,
+    pub job_id: String,
+    pub bpp_id: String,
+    pub bpp_uri: String,
+    #[serde(skip_serializing)]
+    pub request: Value,
+    pub state: String,
+    pub last_message_id: Option<String>,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub order_id: Option<String>,
+    #[serde(skip_serializing)]
+    pub on_init: Option<Value>,
+    pub on_confirm: Option<Value>,
+    pub created_at: DateTime<Utc>,
+    pub modified_at: DateTime<Utc>,
+}
+
+pub struct NewJobApplySaga<'a> {
+    pub transaction_id: &'a str,
+    pub user_id: &'a str,
+    pub job_id: &'a str,
+    pub bpp_id: &'a str,
+    pub bpp_uri: &'a str,
+    pub request: Value,
+    pub message_id: &'a str,
+}
+
+const SAGA_COLUMNS: &str = r#"
+    transaction_id, user_id, job_id, bpp_id, bpp_uri, request, state, last_message_id,
+    attempts, last_error, order_id, on_init, on_confirm, created_at, modified_at
+"#;
+
+/// Starts a saga in INIT_SENT. A FAILED saga with the same transaction_id is
+/// restarted; any other existing saga is left alone and `false` is returned.
+pub async fn start_saga(db_pool: &PgPool, data: NewJobApplySaga<'_>) -> Result<bool, Error> {
+    let result = query(
+        r#"
+        INSERT INTO job_apply_sagas
+            (transaction_id, user_id, job_id, bpp_id, bpp_uri, request, state, last_message_id, attempts)
+        VALUES
+            ($1, $2, $3, $4, $5, $6, 'INIT_SENT', $7, 1)
+        ON CONFLICT (transaction_id) DO UPDATE
+        SET request = EXCLUDED.request,
+            state = 'INIT_SENT',
+            last_message_id = EXCLUDED.last_message_id,
+            attempts = 1,
+            last_error = NULL,
+            on_init = NULL,
+            on_confirm = NULL
+        WHERE job_apply_sagas.state = 'FAILED'
+        "#,
+    )
+    .bind(data.transaction_id)
+    .bind(data.user_id)
+    .bind(data.job_id)
+    .bind(data.bpp_id)
+    .bind(data.bpp_uri)
+    .bind(data.request)
+    .bind(data.message_id)
+    .execute(db_pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn get_saga(
+    db_pool: &PgPool,
+    transaction_id: &str,
+) -> Result<Option<JobApplySaga>, Error> {
+    query_as::<_, JobApplySaga>(&format!(
+        "SELECT {} FROM job_apply_sagas WHERE transaction_id = $1",
+        SAGA_COLUMNS
+    ))
+    .bind(transaction_id)
+    .fetch_optional(db_pool)
+    .await
+}
+
+/// Moves the saga from `from` to `to` before a message is sent, so only one
+/// caller (request handler or sweeper) sends each step. Passing `seen_at`
+/// additionally requires the row to be unchanged since it was read.
+pub async fn claim_step(
+    db_pool: &PgPool,
+    transaction_id: &str,
+    from: &str,
+    to: &str,
+    message_id: &str,
+    seen_at: Option<DateTime<Utc>>,
+) -> Result<bool, Error> {
+    let result = query(
+        r#"
+        UPDATE job_apply_sagas
+        SET state = $3,
+            last_message_id = $4,
+            attempts = CASE WHEN state = $3 THEN attempts + 1 ELSE 1 END
+        WHERE transaction_id = $1
+          AND state = $2
+          AND ($5::timestamptz IS NULL OR modified_at = $5)
+        "#,
+    )
+    .bind(transaction_id)
+    .bind(from)
+    .bind(to)
+    .bind(message_id)
+    .bind(seen_at)
+    .execute(db_pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn mark_init_acked(
+    db_pool: &PgPool,
+    transaction_id: &str,
+    on_init: &Value,
+) -> Result<bool, Error> {
+    let result = query(
+        r#"
+        UPDATE job_apply_sagas
+        SET state = 'INIT_ACKED', on_init = $2
+        WHERE transaction_id = $1 AND state = 'INIT_SENT'
+        "#,
+    )
+    .bind(transaction_id)
+    .bind(on_init)
+    .execute(db_pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Completes the saga. Late callbacks still win over FAILED, since the BPP
+/// has created the order either way. Returns false if it was already confirmed.
+/// Run it in the transaction that stores the application, so a failed insert
+/// leaves the saga open for the next on_confirm.
+pub async fn mark_confirmed(
+    db: impl PgExecutor<'_>,
+    transaction_id: &str,
+    order_id: &str,
+    on_confirm: &Value,
+) -> Result<bool, Error> {
+    let result = query(
+        r#"
+        UPDATE job_apply_sagas
+        SET state = 'CONFIRMED', order_id = $2, on_confirm = $3, last_error = NULL
+        WHERE transaction_id = $1 AND state <> 'CONFIRMED'
+        "#,
+    )
+    .bind(transaction_id)
+    .bind(order_id)
+    .bind(on_confirm)
+    .execute(db)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn mark_failed(db_pool: &PgPool, transaction_id: &str, error: &str) -> Result<(), Error> {
+    query(
+        r#"
+        UPDATE job_apply_sagas
+        SET state = 'FAILED', last_error = $2
+        WHERE transaction_id = $1 AND state NOT IN ('CONFIRMED', 'FAILED')
+        "#,
+    )
+    .bind(transaction_id)
+    .bind(error)
+    .execute(db_pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_stale_sagas(
+    db_pool: &PgPool,
+    stale_after_secs: i64,
+    limit: i64,
+) -> Result<Vec<JobApplySaga>, Error> {
+    query_as::<_, JobApplySaga>(&format!(
+        r#"
+        SELECT {}
+        FROM job_apply_sagas
+        WHERE state IN ('INIT_SENT', 'INIT_ACKED', 'CONFIRM_SENT')
+          AND modified_at < now() - make_interval(secs => $1)
+        ORDER BY modified_at ASC
+        LIMIT $2
+        "#,
+        SAGA_COLUMNS
+    ))
+    .bind(stale_after_secs as f64)
+    .bind(limit)
+    .fetch_all(db_pool)
+    .await
+}
@@ -30,6 +30,17 @@ pub struct MissingMatchRow {
     pub profile_id: Uuid,
 }
 
+#[derive(Debug, FromRow, Clone)]
+pub struct MatchExplainRow {
+    pub job_id: Uuid,
+    pub profile_id: Uuid,
+    pub match_score: Option<i16>,
+    pub score_breakdown: Option<Value>,
+    pub computed_at: Option<chrono::DateTime<chrono::Utc>>,
+    // No stored match, or job/profile changed since it was computed
+    pub stale: bool,
+}
+
 #[derive(Debug, FromRow, Clone)]
 pub struct HighMatchRow {
     pub job_id: uuid::Uuid,
@@ -135,6 +146,99 @@ pub async fn upsert_match_score(
     Ok(())
 }
 
+#[derive(Debug, Clone)]
+pub struct MatchScoreUpsert {
+    pub job_id: Uuid,
+    pub profile_id: Uuid,
+    pub job_hash: String,
+    pub profile_hash: String,
+    pub match_score: i16,
+    pub score_breakdown: Option<Value>,
+}
+
+/// Multi-row `upsert_match_score`; pairs must be unique within one call.
+pub async fn batch_upsert_match_scores(
+    pool: &PgPool,
+    rows: &[MatchScoreUpsert],
+) -> Result<(), sqlx::Error> {
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    let job_ids: Vec<Uuid> = rows.iter().map(|r| r.job_id).collect();
+    let profile_ids: Vec<Uuid> = rows.iter().map(|r| r.profile_id).collect();
+    let job_hashes: Vec<&str> = rows.iter().map(|r| r.job_hash.as_str()).collect();
+    let profile_hashes: Vec<&str> = rows.iter().map(|r| r.profile_hash.as_str()).collect();
+    let scores: Vec<i16> = rows.iter().map(|r| r.match_score).collect();
+    let breakdowns: Vec<Option<Value>> = rows.iter().map(|r| r.score_breakdown.clone()).collect();
+
+    query(
+        r#"
+        INSERT INTO job_profile_matches (
+            job_id,
+            profile_id,
+            job_hash,
+            profile_hash,
+            match_score,
+            score_breakdown,
+            computed_at,
+            updated_at
+        )
+        SELECT u.job_id, u.profile_id, u.job_hash, u.profile_hash, u.match_score, u.score_breakdown, now(), now()
+        FROM UNNEST($1::uuid[], $2::uuid[], $3::text[], $4::text[], $5::int2[], $6::jsonb[])
+            AS u(job_id, profile_id, job_hash, profile_hash, match_score, score_breakdown)
+        ON CONFLICT (job_id, profile_id)
+        DO UPDATE SET
+            job_hash        = EXCLUDED.job_hash,
+            profile_hash    = EXCLUDED.profile_hash,
+            match_score     = EXCLUDED.match_score,
+            score_breakdown = EXCLUDED.score_breakdown,
+            updated_at      = now()
+        "#,
+    )
+    .bind(&job_ids)
+    .bind(&profile_ids)
+    .bind(&job_hashes)
+    .bind(&profile_hashes)
+    .bind(&scores)
+    .bind(&breakdowns)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Stored match for an external profile id and a job (Beckn job id or row id),
+/// with the internal ids even when no match has been computed yet.
+pub async fn fetch_match_explain(
+    pool: &PgPool,
+    profile_id: &str,
+    job_id: &str,
+) -> Result<Option<MatchExplainRow>, sqlx::Error> {
+    query_as::<_, MatchExplainRow>(
+        r#"
+        SELECT
+            j.id AS job_id,
+            p.id AS profile_id,
+            jpm.match_score,
+            jpm.score_breakdown,
+            jpm.computed_at,
+            (jpm.id IS NULL OR jpm.job_hash <> j.hash OR jpm.profile_hash <> p.hash) AS stale
+        FROM profiles p
+        JOIN jobs j ON (j.job_id = $2 OR j.id::text = $2)
+        LEFT JOIN job_profile_matches jpm
+          ON jpm.profile_id = p.id AND jpm.job_id = j.id
+        WHERE p.profile_id = $1
+        ORDER BY j.is_active DESC, jpm.computed_at DESC NULLS LAST
+        LIMIT 1
+        "#,
+    )
+    .bind(profile_id)
+    .bind(job_id)
+    .fetch_optional(pool)
+    .await
+}
+
 pub async fn fetch_all_jobs(pool: &PgPool) -> Result<Vec<JobRow>, sqlx::Error> {
     sqlx::query_as::<_, JobRow>(
         r#"
@@ -160,7 +264,11 @@ pub async fn fetch_all_profiles(pool: &PgPool) -> Result<Vec<ProfileRow>, sqlx::
             id,
             hash,
             metadata,
-            beckn_structure
+            beckn_structure,
+            embedding,
+            embedding_model,
+            embedding_dim,
+            embedding_rules_hash
         FROM profiles
         "#,
     )
@@ -264,7 +372,8 @@ pub async fn fetch_jobs_with_matches(
                 SELECT jsonb_build_object(
                      'job', to_jsonb(j.*) - 'embedding',
                     'profile_id', p.profile_id,
-                    'match_score', jpm.match_score
+                    'match_score', jpm.match_score,
+                    'score_breakdown', jpm.score_breakdown
                 )
                 FROM job_profile_matches jpm
                 JOIN jobs j ON j.id = jpm.job_id
@@ -518,3 +627,116 @@ pub async fn fetch_high_match_scores(
     .fetch_all(db_pool)
     .await
 }
+
+/// Active, embedded jobs whose current version hasn't been through candidate scoring.
+pub async fn fetch_unmatched_jobs(
+    pool: &PgPool,
+    model: &str,
+    dimension: i32,
+) -> Result<Vec<JobLiteRow>, sqlx::Error> {
+    query_as::<_, JobLiteRow>(
+        r#"
+        SELECT id, hash
+        FROM jobs
+        WHERE is_active = true
+          AND embedding IS NOT NULL
+          AND embedding_model = $1
+          AND embedding_dim = $2
+          AND matched_hash IS DISTINCT FROM hash
+        "#,
+    )
+    .bind(model)
+    .bind(dimension)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn fetch_unmatched_profiles(pool: &PgPool) -> Result<Vec<ProfileLiteRow>, sqlx::Error> {
+    query_as::<_, ProfileLiteRow>(
+        r#"
+        SELECT id, hash
+        FROM profiles
+        WHERE matched_hash IS DISTINCT FROM hash
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Records the hash each job was scored at; a job edited meanwhile stays unmatched.
+pub async fn mark_jobs_matched(pool: &PgPool, jobs: &[(Uuid, String)]) -> Result<(), sqlx::Error> {
+    if jobs.is_empty() {
+        return Ok(());
+    }
+
+    let ids: Vec<Uuid> = jobs.iter().map(|(id, _)| *id).collect();
+    let hashes: Vec<&str> = jobs.iter().map(|(_, hash)| hash.as_str()).collect();
+
+    query(
+        r#"
+        UPDATE jobs j
+        SET matched_hash = u.hash
+        FROM UNNEST($1::uuid[], $2::text[]) AS u(id, hash)
+        WHERE j.id = u.id
+          AND j.hash = u.hash
+        "#,
+    )
+    .bind(&ids)
+    .bind(&hashes)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn mark_profiles_matched(
+    pool: &PgPool,
+    profiles: &[(Uuid, String)],
+) -> Result<(), sqlx::Error> {
+    if profiles.is_empty() {
+        return Ok(());
+    }
+
+    let ids: Vec<Uuid> = profiles.iter().map(|(id, _)| *id).collect();
+    let hashes: Vec<&str> = profiles.iter().map(|(_, hash)| hash.as_str()).collect();
+
+    query(
+        r#"
+        UPDATE profiles p
+        SET matched_hash = u.hash
+        FROM UNNEST($1::uuid[], $2::text[]) AS u(id, hash)
+        WHERE p.id = u.id
+          AND p.hash = u.hash
+        "#,
+    )
+    .bind(&ids)
+    .bind(&hashes)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn delete_match_scores(pool: &PgPool, pairs: &[(Uuid, Uuid)]) -> Result<(), sqlx::Error> {
+    if pairs.is_empty() {
+        return Ok(());
+    }
+
+    let job_ids: Vec<Uuid> = pairs.iter().map(|(job_id, _)| *job_id).collect();
+    let profile_ids: Vec<Uuid> = pairs.iter().map(|(_, profile_id)| *profile_id).collect();
+
+    query(
+        r#"
+        DELETE FROM job_profile_matches m
+        USING UNNEST($1::uuid[], $2::uuid[]) AS u(job_id, profile_id)
+        WHERE m.job_id = u.job_id
+          AND m.profile_id = u.profile_id
+        "#,
+    )
+    .bind(&job_ids)
+    .bind(&profile_ids)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
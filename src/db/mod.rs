@@ -1,5 +1,8 @@
+pub mod beckn_messages;
 pub mod job;
+pub mod job_application_status_history;
 pub mod job_applications;
+pub mod job_apply_sagas;
 pub mod job_draft;
 pub mod match_score;
 pub mod profiles;
@@ -19,6 +19,21 @@ pub struct ProfileRow {
     pub hash: String,
     pub metadata: Option<Value>,
     pub beckn_structure: Option<Value>,
+    pub embedding: Option<Vec<f32>>,
+    pub embedding_model: Option<String>,
+    pub embedding_dim: Option<i32>,
+    pub embedding_rules_hash: Option<String>,
+}
+
+impl ProfileRow {
+    /// Stored vector, if it was produced by the given model and dimension.
+    pub fn current_embedding(&self, model: &str, dimension: usize) -> Option<&[f32]> {
+        let embedding = self.embedding.as_deref()?;
+        (self.embedding_model.as_deref() == Some(model)
+            && self.embedding_dim == Some(dimension as i32)
+            && embedding.len() == dimension)
+            .then_some(embedding)
+    }
 }
 
 #[derive(FromRow, Debug)]
@@ -115,6 +130,26 @@ pub async fn store_profiles(db_pool: &PgPool, profiles: &[NewProfile]) -> Result
                 THEN EXCLUDED.hash
                 ELSE profiles.hash
             END,
+            embedding = CASE
+                WHEN profiles.hash IS DISTINCT FROM EXCLUDED.hash
+                THEN NULL
+                ELSE profiles.embedding
+            END,
+            embedding_model = CASE
+                WHEN profiles.hash IS DISTINCT FROM EXCLUDED.hash
+                THEN NULL
+                ELSE profiles.embedding_model
+            END,
+            embedding_dim = CASE
+                WHEN profiles.hash IS DISTINCT FROM EXCLUDED.hash
+                THEN NULL
+                ELSE profiles.embedding_dim
+            END,
+            embedding_rules_hash = CASE
+                WHEN profiles.hash IS DISTINCT FROM EXCLUDED.hash
+                THEN NULL
+                ELSE profiles.embedding_rules_hash
+            END,
             updated_at = CASE
                 WHEN profiles.hash IS DISTINCT FROM EXCLUDED.hash
                 THEN now()
@@ -214,7 +249,11 @@ pub async fn fetch_profile_by_id(
             id,
             hash,
             metadata,
-            beckn_structure
+            beckn_structure,
+            embedding,
+            embedding_model,
+            embedding_dim,
+            embedding_rules_hash
         FROM profiles
         WHERE id = $1
         "#,
@@ -224,6 +263,157 @@ pub async fn fetch_profile_by_id(
     .await
 }
 
+pub async fn fetch_profile_by_profile_id(
+    pool: &PgPool,
+    profile_id: &str,
+) -> Result<Option<ProfileRow>, sqlx::Error> {
+    query_as::<_, ProfileRow>(
+        r#"
+        SELECT
+            id,
+            hash,
+            metadata,
+            beckn_structure,
+            embedding,
+            embedding_model,
+            embedding_dim,
+            embedding_rules_hash
+        FROM profiles
+        WHERE profile_id = $1
+        "#,
+    )
+    .bind(profile_id)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn fetch_profiles_by_ids(
+    pool: &PgPool,
+    profile_ids: &[Uuid],
+) -> Result<Vec<ProfileRow>, sqlx::Error> {
+    if profile_ids.is_empty() {
+        return Ok(vec![]);
+    }
+
+    query_as::<_, ProfileRow>(
+        r#"
+        SELECT
+            id,
+            hash,
+            metadata,
+            beckn_structure,
+            embedding,
+            embedding_model,
+            embedding_dim,
+            embedding_rules_hash
+        FROM profiles
+        WHERE id = ANY($1)
+        "#,
+    )
+    .bind(profile_ids)
+    .fetch_all(pool)
+    .await
+}
+
+/// Profiles without a vector from the given model, dimension and embed rules,
+/// ordered by id.
+pub async fn fetch_profiles_pending_embedding(
+    pool: &PgPool,
+    model: &str,
+    dimension: i32,
+    rules_hash: &str,
+    after: Option<Uuid>,
+    limit: i64,
+) -> Result<Vec<ProfileRow>, sqlx::Error> {
+    query_as::<_, ProfileRow>(
+        r#"
+        SELECT
+            id,
+            hash,
+            metadata,
+            beckn_structure,
+            embedding,
+            embedding_model,
+            embedding_dim,
+            embedding_rules_hash
+        FROM profiles
+        WHERE (
+            embedding IS NULL
+            OR embedding_model IS DISTINCT FROM $1
+            OR embedding_dim IS DISTINCT FROM $2
+            OR embedding_rules_hash IS DISTINCT FROM $3
+          )
+          AND ($4::uuid IS NULL OR id > $4)
+        ORDER BY id
+        LIMIT $5
+        "#,
+    )
+    .bind(model)
+    .bind(dimension)
+    .bind(rules_hash)
+    .bind(after)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+/// Stamps profile vectors stored before rules hashing with the live rules.
+pub async fn adopt_unhashed_profile_embeddings(
+    pool: &PgPool,
+    rules_hash: &str,
+) -> Result<u64, sqlx::Error> {
+    let result = query(
+        r#"
+        UPDATE profiles
+        SET embedding_rules_hash = $1
+        WHERE embedding IS NOT NULL
+          AND embedding_rules_hash IS NULL
+        "#,
+    )
+    .bind(rules_hash)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+pub async fn batch_update_profile_embeddings(
+    pool: &PgPool,
+    updates: &[(Uuid, Vec<f32>)],
+    model: &str,
+    dimension: i32,
+    rules_hash: &str,
+) -> Result<(), sqlx::Error> {
+    if updates.is_empty() {
+        return Ok(());
+    }
+
+    let ids: Vec<Uuid> = updates.iter().map(|(id, _)| *id).collect();
+    // Postgres arrays can't be ragged, so vectors travel as JSON.
+    let embeddings: Vec<Value> = updates.iter().map(|(_, e)| json!(e)).collect();
+
+    query(
+        r#"
+        UPDATE profiles p
+        SET embedding = ARRAY(SELECT jsonb_array_elements_text(t.embedding)::float4),
+            embedding_model = $3,
+            embedding_dim = $4,
+            embedding_rules_hash = $5
+        FROM UNNEST($1::uuid[], $2::jsonb[]) AS t(id, embedding)
+        WHERE p.id = t.id
+        "#,
+    )
+    .bind(&ids)
+    .bind(&embeddings)
+    .bind(model)
+    .bind(dimension)
+    .bind(rules_hash)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
 pub async fn get_or_sync_profile(
     state: &Arc<AppState>,
     profile_id: &str,
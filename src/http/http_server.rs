@@ -1,5 +1,7 @@
 use crate::cron::start_cron_jobs;
 use crate::workers::redis_event_worker::start as start_redis_worker;
+use crate::workers::reembed::start as start_reembed;
+use crate::workers::response_listener::start as start_response_listener;
 use crate::{
     config::AppConfig,
     http::routes::create_routes,
@@ -10,7 +12,9 @@ use std::sync::Arc;
 use tokio::{net::TcpListener, sync::watch, task::JoinHandle};
 use tracing::info;
 
-use crate::vector::faiss_service::FaissService;
+use crate::services::empeding::build_embedding_service;
+use crate::utils::match_rules::{spawn_watcher as spawn_match_rules_watcher, MatchRulesHandle};
+use crate::vector::faiss_service::{FaissService, IndexSpec};
 use crate::vector::index_store::load_faiss;
 use deadpool_redis::{Config as RedisConfig, Pool, Runtime};
 use tokio::sync::RwLock;
@@ -38,29 +42,72 @@ pub async fn start_http_server(
         info!("✅ Redis PING -> {}", pong);
     }
 
+    // Dedicated client for pub/sub, which needs its own connection
+    let redis_client = redis::Client::open(config.redis.url.as_str())?;
+
     // --- Postgres pool ---
     let db_pool = PgPool::connect(&config.db.url).await?;
     info!("✅ connected to db at {}", &config.db.url);
 
-    let faiss = load_faiss(config.gcp.dimension, redis_pool.clone()).unwrap_or_else(|_| {
-        info!("⚠️ No FAISS index found, creating new one");
-        FaissService::new(config.gcp.dimension, redis_pool.clone())
-    });
+    let embedding = build_embedding_service(&config)?;
+
+    let index_spec = IndexSpec::from_config(
+        &config.vector,
+        config.embedding.dimension,
+        embedding.model(),
+    );
+    info!("✅ FAISS index spec: {:?}", index_spec);
+
+    let faiss = match load_faiss(&config.vector, index_spec.clone()) {
+        Ok(faiss) => faiss,
+        Err(e) => {
+            info!("⚠️ No usable FAISS index ({}), creating new one", e);
+            FaissService::new(&config.vector, index_spec)?
+        }
+    };
 
     let faiss = Arc::new(RwLock::new(faiss));
 
+    let match_rules = Arc::new(MatchRulesHandle::load(&config.match_score.config_path)?);
+    info!(
+        "✅ Loaded match rules from {}",
+        config.match_score.config_path
+    );
+
     let app_state = Arc::new(AppState {
         config: Arc::new(config.clone()),
         shared_state,
         redis_pool,
+        redis_client,
         db_pool,
         faiss,
+        embedding,
+        match_rules,
     });
 
+    spawn_match_rules_watcher(
+        app_state.clone(),
+        app_state.config.match_score.reload_interval_secs,
+    );
+
     let _scheduler = start_cron_jobs(app_state.clone()).await;
 
     let http_server = tokio::spawn(run_http_server(listener, shutdown_rx, app_state.clone()));
 
+    {
+        let app_state = app_state.clone();
+        tokio::spawn(async move {
+            start_response_listener(app_state).await;
+        });
+    }
+
+    {
+        let app_state = app_state.clone();
+        tokio::spawn(async move {
+            start_reembed(app_state).await;
+        });
+    }
+
     {
         tokio::spawn(async move {
             start_redis_worker(app_state.clone()).await;
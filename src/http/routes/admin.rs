@@ -1,9 +1,34 @@
-use crate::services::admin::rebuild_faiss_service;
+use crate::services::admin::{
+    faiss_consistency_service, faiss_repair_service, faiss_stats_service, match_explain_service,
+    match_score_progress_service, rebuild_faiss_progress_service, rebuild_faiss_service,
+    reembed_progress_service, transaction_log_service, webhook_metrics_service,
+};
 use crate::state::AppState;
-use axum::{routing::post, Router};
+use axum::{
+    routing::{get, post},
+    Router,
+};
 use std::sync::Arc;
 pub fn routes(app_state: Arc<AppState>) -> Router {
     Router::new()
         .route("/admin/faiss/rebuild", post(rebuild_faiss_service))
+        .route(
+            "/admin/faiss/rebuild/{job_id}",
+            get(rebuild_faiss_progress_service),
+        )
+        .route("/admin/faiss/stats", get(faiss_stats_service))
+        .route("/admin/faiss/consistency", get(faiss_consistency_service))
+        .route("/admin/faiss/repair", post(faiss_repair_service))
+        .route("/admin/embeddings/reembed", get(reembed_progress_service))
+        .route(
+            "/admin/match-scores/progress",
+            get(match_score_progress_service),
+        )
+        .route(
+            "/admin/matches/{profile_id}/{job_id}/explain",
+            get(match_explain_service),
+        )
+        .route("/admin/metrics/webhooks", get(webhook_metrics_service))
+        .route("/admin/transactions/{txn_id}", get(transaction_log_service))
         .with_state(app_state)
 }
@@ -1,8 +1,12 @@
+use crate::services::application_status::handle_application_timeline;
+use crate::services::apply_saga::handle_apply_status;
+use crate::services::cancel::handle_cancel_application;
 use crate::services::job_apply::{handle_job_applications, handle_job_apply, handle_job_apply_v2};
 use crate::services::job_draft::{
     create_user_draft_application, delete_user_draft_application, get_user_draft_applications,
     update_user_draft_application,
 };
+use crate::services::similar_jobs::handle_similar_jobs;
 use crate::state::AppState;
 use axum::{
     routing::{delete, get, patch, post},
@@ -12,7 +16,17 @@ use std::sync::Arc;
 pub fn routes(app_state: Arc<AppState>) -> Router {
     Router::new()
         .route("/v1/apply", post(handle_job_apply))
+        .route("/v1/apply/{transaction_id}", get(handle_apply_status))
         .route("/v1/job-applications", get(handle_job_applications))
+        .route("/v1/jobs/{job_id}/similar", get(handle_similar_jobs))
+        .route(
+            "/v1/applications/{order_id}/cancel",
+            post(handle_cancel_application),
+        )
+        .route(
+            "/v1/applications/{order_id}/timeline",
+            get(handle_application_timeline),
+        )
         .route(
             "/v1/job-applications/drafts",
             post(create_user_draft_application),
@@ -1,8 +1,12 @@
 use crate::services::search::{
     handle_search, handle_search_v2, handle_search_v3, handle_top_results,
 };
+use crate::services::search_stream::handle_search_stream;
 use crate::state::AppState;
-use axum::{routing::post, Router};
+use axum::{
+    routing::{get, post},
+    Router,
+};
 use std::sync::Arc;
 
 pub fn routes(app_state: Arc<AppState>) -> Router {
@@ -11,5 +15,6 @@ pub fn routes(app_state: Arc<AppState>) -> Router {
         .route("/v2/search", post(handle_search_v2))
         .route("/v3/search", post(handle_search_v3))
         .route("/v1/search/top", post(handle_top_results))
+        .route("/v1/search/stream", get(handle_search_stream))
         .with_state(app_state)
 }
@@ -1,11 +1,27 @@
+use crate::middleware::beckn_message_log::log_incoming_webhook;
+use crate::middleware::beckn_signature::verify_beckn_signature;
+use crate::middleware::webhook_dedupe::dedupe_webhook;
 use crate::services::webhook::{webhook_handler, webhook_handler_profiles};
 use crate::state::AppState;
-use axum::{routing::post, Router};
+use axum::{middleware, routing::post, Router};
 use std::sync::Arc;
 
 pub fn routes(app_state: Arc<AppState>) -> Router {
     Router::new()
         .route("/webhook/{action}", post(webhook_handler))
         .route("/webhook/profiles/{action}", post(webhook_handler_profiles))
+        // Layers run bottom-up: signatures are checked before anything is recorded for dedupe.
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            dedupe_webhook,
+        ))
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            verify_beckn_signature,
+        ))
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            log_incoming_webhook,
+        ))
         .with_state(app_state)
 }
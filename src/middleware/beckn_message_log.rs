@@ -0,0 +1,58 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::State,
+    http::{Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::error;
+
+use crate::state::AppState;
+use crate::utils::beckn_log::{log_beckn_exchange, BecknExchange, DIRECTION_INCOMING};
+
+const MAX_WEBHOOK_BODY_BYTES: usize = 10 * 1024 * 1024;
+
+/// Logs every `/webhook/*` call, including ones rejected by later layers.
+pub async fn log_incoming_webhook(
+    State(state): State<Arc<AppState>>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let started = Instant::now();
+    let (parts, body) = req.into_parts();
+
+    let bytes = match to_bytes(body, MAX_WEBHOOK_BODY_BYTES).await {
+        Ok(b) => b,
+        Err(e) => {
+            error!("❌ Failed to read webhook body: {:?}", e);
+            return StatusCode::BAD_REQUEST.into_response();
+        }
+    };
+
+    let path = parts.uri.path().to_string();
+    let payload = serde_json::from_slice::<Value>(&bytes).ok();
+
+    let response = next
+        .run(Request::from_parts(parts, Body::from(bytes)))
+        .await;
+
+    let status = response.status();
+    log_beckn_exchange(
+        &state.db_pool,
+        BecknExchange {
+            direction: DIRECTION_INCOMING,
+            fallback_action: path.rsplit('/').next().unwrap_or_default(),
+            url: Some(&path),
+            payload: payload.as_ref(),
+            http_status: Some(status.as_u16()),
+            latency: started.elapsed(),
+            error: (!status.is_success())
+                .then(|| status.canonical_reason().unwrap_or_default().to_string()),
+        },
+    );
+
+    response
+}
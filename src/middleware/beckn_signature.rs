@@ -0,0 +1,126 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::State,
+    http::{header, HeaderValue, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::models::webhook::{Ack, AckResponse, AckStatus};
+use crate::state::AppState;
+use crate::utils::registry::lookup_signing_public_key;
+use crate::utils::signing::{parse_authorization_header, verify_signature, SIGNED_HEADERS};
+
+const MAX_WEBHOOK_BODY_BYTES: usize = 10 * 1024 * 1024;
+
+fn unauthorized(state: &AppState) -> Response {
+    let mut response = (
+        StatusCode::UNAUTHORIZED,
+        Json(AckResponse {
+            message: AckStatus {
+                ack: Ack { status: "NACK" },
+            },
+        }),
+    )
+        .into_response();
+
+    let challenge = format!(
+        "Signature realm=\"{}\",headers=\"{}\"",
+        state.config.bap.id, SIGNED_HEADERS
+    );
+    if let Ok(value) = HeaderValue::from_str(&challenge) {
+        response
+            .headers_mut()
+            .insert(header::WWW_AUTHENTICATE, value);
+    }
+
+    response
+}
+
+pub async fn verify_beckn_signature(
+    State(state): State<Arc<AppState>>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    if !state.config.signing.verify_incoming {
+        return next.run(req).await;
+    }
+
+    let (parts, body) = req.into_parts();
+
+    let bytes = match to_bytes(body, MAX_WEBHOOK_BODY_BYTES).await {
+        Ok(b) => b,
+        Err(e) => {
+            error!("❌ Failed to read webhook body: {:?}", e);
+            return StatusCode::BAD_REQUEST.into_response();
+        }
+    };
+
+    let params = match parts
+        .headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .map(parse_authorization_header)
+    {
+        Some(Ok(p)) => p,
+        Some(Err(e)) => {
+            info!("⚠️ Rejecting webhook with malformed signature: {}", e);
+            return unauthorized(&state);
+        }
+        None => {
+            info!("⚠️ Rejecting unsigned webhook on {}", parts.uri.path());
+            return unauthorized(&state);
+        }
+    };
+
+    // The signer must be the participant the payload claims to come from,
+    // otherwise any registered subscriber could overwrite another BPP's results.
+    let claimed_sender = serde_json::from_slice::<Value>(&bytes).ok().and_then(|v| {
+        let ctx = v.get("context")?;
+        let is_callback = ctx
+            .get("action")
+            .and_then(|a| a.as_str())
+            .is_some_and(|a| a.starts_with("on_"));
+        let sender_field = if is_callback { "bpp_id" } else { "bap_id" };
+        ctx.get(sender_field)
+            .and_then(|id| id.as_str())
+            .map(|id| id.to_string())
+    });
+
+    if let Some(sender) = claimed_sender {
+        if sender != params.subscriber_id {
+            info!(
+                "⚠️ Rejecting webhook: signed by {} but context claims {}",
+                params.subscriber_id, sender
+            );
+            return unauthorized(&state);
+        }
+    }
+
+    let public_key =
+        match lookup_signing_public_key(&state, &params.subscriber_id, &params.unique_key_id).await
+        {
+            Ok(k) => k,
+            Err(e) => {
+                info!("⚠️ Rejecting webhook: registry lookup failed: {}", e);
+                return unauthorized(&state);
+            }
+        };
+
+    if let Err(e) = verify_signature(
+        &params,
+        &bytes,
+        &public_key,
+        state.config.signing.max_clock_skew_secs,
+    ) {
+        info!("⚠️ Rejecting webhook from {}: {}", params.subscriber_id, e);
+        return unauthorized(&state);
+    }
+
+    next.run(Request::from_parts(parts, Body::from(bytes)))
+        .await
+}
@@ -1 +1,4 @@
 pub mod api_key;
+pub mod beckn_message_log;
+pub mod beckn_signature;
+pub mod webhook_dedupe;
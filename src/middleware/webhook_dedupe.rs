@@ -0,0 +1,117 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::State,
+    http::{Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use deadpool_redis::redis::cmd;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::models::webhook::{Ack, AckResponse, AckStatus};
+use crate::state::AppState;
+use crate::utils::metrics::record_webhook_duplicate;
+
+const MAX_WEBHOOK_BODY_BYTES: usize = 10 * 1024 * 1024;
+
+fn dedupe_key(body: &[u8]) -> Option<(String, String)> {
+    let payload: Value = serde_json::from_slice(body).ok()?;
+    let ctx = payload.get("context")?;
+    let field = |name: &str| ctx.get(name).and_then(|v| v.as_str());
+
+    let action = field("action")?;
+    // Incoming requests on the profiles side have no bpp_id; the BAP is the sender.
+    let sender = field("bpp_id").or_else(|| field("bap_id")).unwrap_or("");
+
+    let key = format!(
+        "webhook:dedupe:{}:{}:{}:{}",
+        sender,
+        field("transaction_id")?,
+        field("message_id")?,
+        action
+    );
+
+    Some((key, action.to_string()))
+}
+
+/// ACKs retried callbacks without handing them to the webhook handlers again.
+pub async fn dedupe_webhook(
+    State(state): State<Arc<AppState>>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let window = state.config.cache.webhook_dedupe_secs;
+    if window == 0 {
+        return next.run(req).await;
+    }
+
+    let (parts, body) = req.into_parts();
+
+    let bytes = match to_bytes(body, MAX_WEBHOOK_BODY_BYTES).await {
+        Ok(b) => b,
+        Err(e) => {
+            error!("❌ Failed to read webhook body: {:?}", e);
+            return StatusCode::BAD_REQUEST.into_response();
+        }
+    };
+
+    // Malformed payloads are left for the handler's extractor to reject.
+    let dedupe = dedupe_key(&bytes);
+    if let Some((key, action)) = &dedupe {
+        let first_seen = match state.redis_pool.get().await {
+            Ok(mut conn) => {
+                let set: Result<Option<String>, _> = cmd("SET")
+                    .arg(key)
+                    .arg(1)
+                    .arg("NX")
+                    .arg("EX")
+                    .arg(window)
+                    .query_async(&mut conn)
+                    .await;
+
+                match set {
+                    Ok(reply) => reply.is_some(),
+                    Err(e) => {
+                        // Fail open: reprocessing beats dropping a callback.
+                        error!("❌ Webhook dedupe check failed: {:?}", e);
+                        true
+                    }
+                }
+            }
+            Err(e) => {
+                error!("❌ Failed to get Redis connection from pool: {:?}", e);
+                true
+            }
+        };
+
+        if !first_seen {
+            info!("♻️ Duplicate webhook ignored: {}", key);
+            if let Err(e) = record_webhook_duplicate(&state, action).await {
+                error!("❌ Failed to record duplicate webhook metric: {:?}", e);
+            }
+
+            return Json(AckResponse {
+                message: AckStatus {
+                    ack: Ack { status: "ACK" },
+                },
+            })
+            .into_response();
+        }
+    }
+
+    let response = next
+        .run(Request::from_parts(parts, Body::from(bytes)))
+        .await;
+
+    // A NACKed or failed callback wasn't processed, so let the retry through.
+    if !response.status().is_success() {
+        if let (Some((key, _)), Ok(mut conn)) = (dedupe, state.redis_pool.get().await) {
+            let _: Result<(), _> = cmd("DEL").arg(&key).query_async(&mut conn).await;
+        }
+    }
+
+    response
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CancelApplicationRequest {
+    pub cancelled_by: String,
+    pub reason_code: String,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CancelMessage {
+    pub order_id: String,
+    pub cancellation_reason_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub descriptor: Option<CancelDescriptor>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CancelDescriptor {
+    pub short_desc: String,
+}
@@ -11,6 +11,8 @@ pub struct Context {
     pub transaction_id: String,
     pub message_id: String,
     pub timestamp: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bpp_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1,3 +1,4 @@
+pub mod cancel;
 pub mod core;
 pub mod events;
 pub mod job_apply;
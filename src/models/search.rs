@@ -86,6 +86,19 @@ pub struct SearchMessage {
 pub struct SearchRequest {
     pub message: SearchMessage,
 }
+#[derive(Debug, Deserialize)]
+pub struct SearchStreamQuery {
+    pub query: Option<String>,
+    pub provider: Option<String>,
+    pub page: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SimilarJobsQuery {
+    pub limit: Option<u32>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SearchRequestV2 {
     pub provider: Option<String>,
@@ -97,12 +110,16 @@ pub struct SearchRequestV2 {
     pub profile: Option<serde_json::Value>,
     pub exclude: Option<String>,
     pub profile_id: Option<String>,
+    // v3: keep each match's stored score_breakdown in the results
+    pub explain: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SearchTopKRequest {
     pub limit: Option<u32>,
 
+    pub bpp_id: Option<String>,
+
     pub role: Option<String>,
     pub industry: Option<String>,
     pub location: Option<String>,
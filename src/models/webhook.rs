@@ -10,7 +10,12 @@ pub struct HealthResponse {
 #[derive(Debug, Deserialize, Serialize)]
 pub struct WebhookPayload {
     pub context: Context,
+    // Absent (null) on some failure callbacks
+    #[serde(default)]
     pub message: Value,
+    // Set by BPPs on failed on_* callbacks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<Value>,
 }
 
 #[derive(Debug, Serialize)]
@@ -27,3 +32,27 @@ pub struct AckStatus {
 pub struct Ack {
     pub status: &'static str,
 }
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BecknError {
+    pub code: String,
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NackResponse {
+    pub message: AckStatus,
+    pub error: BecknError,
+}
+
+impl NackResponse {
+    pub fn new(error: BecknError) -> Self {
+        NackResponse {
+            message: AckStatus {
+                ack: Ack { status: "NACK" },
+            },
+            error,
+        }
+    }
+}
@@ -1,27 +1,239 @@
+use crate::db::beckn_messages::get_beckn_messages_by_txn;
+use crate::services::matches::explain_match;
 use crate::state::AppState;
-use crate::vector::index_store::rebuild_faiss_from_db;
-use axum::{extract::State, http::StatusCode, Json};
+use crate::utils::match_progress;
+use crate::utils::metrics::get_webhook_duplicate_counts;
+use crate::vector::consistency;
+use crate::workers::faiss_rebuild;
+use crate::workers::reembed::get_progress;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
 use serde_json::{json, Value as JsonValue};
 use std::sync::Arc;
 use tracing::error;
 
 pub async fn rebuild_faiss_service(
     State(app_state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<JsonValue>), (StatusCode, Json<JsonValue>)> {
+    match faiss_rebuild::spawn(app_state.clone()).await {
+        Ok(job_id) => Ok((
+            StatusCode::ACCEPTED,
+            Json(json!({
+                "status": "ok",
+                "message": "FAISS rebuild started",
+                "job_id": job_id
+            })),
+        )),
+        Err(e) if e.is::<faiss_rebuild::RebuildInProgress>() => Err((
+            StatusCode::CONFLICT,
+            Json(json!({
+                "status": "error",
+                "message": e.to_string()
+            })),
+        )),
+        Err(e) => {
+            error!("Failed to start FAISS rebuild: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "status": "error",
+                    "message": format!("Failed to start FAISS rebuild: {}", e)
+                })),
+            ))
+        }
+    }
+}
+
+pub async fn rebuild_faiss_progress_service(
+    Path(job_id): Path<String>,
+    State(app_state): State<Arc<AppState>>,
+) -> Result<Json<JsonValue>, (StatusCode, Json<JsonValue>)> {
+    match faiss_rebuild::get_progress(&app_state, &job_id).await {
+        Ok(progress) if progress.is_empty() => Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "status": "error",
+                "message": format!("No FAISS rebuild job {}", job_id)
+            })),
+        )),
+        Ok(progress) => Ok(Json(json!({
+            "status": "ok",
+            "job_id": job_id,
+            "rebuild": progress
+        }))),
+        Err(e) => {
+            error!("Failed to read FAISS rebuild progress: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "status": "error",
+                    "message": format!("Failed to read FAISS rebuild progress: {}", e)
+                })),
+            ))
+        }
+    }
+}
+
+pub async fn webhook_metrics_service(
+    State(app_state): State<Arc<AppState>>,
+) -> Result<Json<JsonValue>, (StatusCode, Json<JsonValue>)> {
+    match get_webhook_duplicate_counts(&app_state).await {
+        Ok(by_action) => {
+            let total: u64 = by_action.values().sum();
+            Ok(Json(json!({
+                "status": "ok",
+                "duplicates_total": total,
+                "duplicates_by_action": by_action
+            })))
+        }
+        Err(e) => {
+            error!("Failed to read webhook metrics: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "status": "error",
+                    "message": format!("Failed to read webhook metrics: {}", e)
+                })),
+            ))
+        }
+    }
+}
+
+pub async fn transaction_log_service(
+    Path(txn_id): Path<String>,
+    State(app_state): State<Arc<AppState>>,
+) -> Result<Json<JsonValue>, (StatusCode, Json<JsonValue>)> {
+    match get_beckn_messages_by_txn(&app_state.db_pool, &txn_id).await {
+        Ok(messages) if messages.is_empty() => Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "status": "error",
+                "message": format!("No Beckn messages recorded for transaction {}", txn_id)
+            })),
+        )),
+        Ok(messages) => Ok(Json(json!({
+            "status": "ok",
+            "transaction_id": txn_id,
+            "messages": messages
+        }))),
+        Err(e) => {
+            error!("Failed to load transaction log: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "status": "error",
+                    "message": format!("Failed to load transaction log: {}", e)
+                })),
+            ))
+        }
+    }
+}
+
+pub async fn reembed_progress_service(
+    State(app_state): State<Arc<AppState>>,
+) -> Result<Json<JsonValue>, (StatusCode, Json<JsonValue>)> {
+    match get_progress(&app_state).await {
+        Ok(progress) => Ok(Json(json!({
+            "status": "ok",
+            "model": app_state.embedding.model(),
+            "dimension": app_state.embedding.dimension(),
+            "reembed": progress
+        }))),
+        Err(e) => {
+            error!("Failed to read re-embedding progress: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "status": "error",
+                    "message": format!("Failed to read re-embedding progress: {}", e)
+                })),
+            ))
+        }
+    }
+}
+
+/// Per-rule score breakdown for a profile/job match.
+pub async fn match_explain_service(
+    Path((profile_id, job_id)): Path<(String, String)>,
+    State(app_state): State<Arc<AppState>>,
+) -> Result<Json<JsonValue>, (StatusCode, Json<JsonValue>)> {
+    explain_match(&app_state, &profile_id, &job_id).await
+}
+
+pub async fn match_score_progress_service(
+    State(app_state): State<Arc<AppState>>,
+) -> Result<Json<JsonValue>, (StatusCode, Json<JsonValue>)> {
+    match match_progress::get_progress(&app_state).await {
+        Ok(progress) => Ok(Json(json!({
+            "status": "ok",
+            "match_scores": progress
+        }))),
+        Err(e) => {
+            error!("Failed to read match-score progress: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "status": "error",
+                    "message": format!("Failed to read match-score progress: {}", e)
+                })),
+            ))
+        }
+    }
+}
+
+pub async fn faiss_stats_service(
+    State(app_state): State<Arc<AppState>>,
 ) -> Result<Json<JsonValue>, (StatusCode, Json<JsonValue>)> {
-    let dimension = app_state.config.gcp.dimension;
+    Ok(Json(json!({
+        "status": "ok",
+        "index": consistency::index_stats(&app_state).await
+    })))
+}
 
-    match rebuild_faiss_from_db(&app_state, dimension).await {
-        Ok(_) => Ok(Json(json!({
+pub async fn faiss_consistency_service(
+    State(app_state): State<Arc<AppState>>,
+) -> Result<Json<JsonValue>, (StatusCode, Json<JsonValue>)> {
+    match consistency::check(&app_state).await {
+        Ok(report) => Ok(Json(json!({
+            "status": "ok",
+            "consistent": report.is_consistent(),
+            "missing_count": report.missing.len(),
+            "orphaned_count": report.orphaned.len(),
+            "dimension_mismatch_count": report.dimension_mismatch.len(),
+            "report": report
+        }))),
+        Err(e) => {
+            error!("FAISS consistency check failed: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "status": "error",
+                    "message": format!("FAISS consistency check failed: {}", e)
+                })),
+            ))
+        }
+    }
+}
+
+pub async fn faiss_repair_service(
+    State(app_state): State<Arc<AppState>>,
+) -> Result<Json<JsonValue>, (StatusCode, Json<JsonValue>)> {
+    match consistency::repair(&app_state).await {
+        Ok(summary) => Ok(Json(json!({
             "status": "ok",
-            "message": "FAISS rebuild completed successfully"
+            "message": "FAISS repair completed",
+            "repair": summary
         }))),
         Err(e) => {
-            error!("FAISS rebuild failed: {}", e);
+            error!("FAISS repair failed: {}", e);
             Err((
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(json!({
                     "status": "error",
-                    "message": format!("FAISS rebuild failed: {}", e)
+                    "message": format!("FAISS repair failed: {}", e)
                 })),
             ))
         }
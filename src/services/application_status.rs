@@ -0,0 +1,164 @@
+use crate::db::job_application_status_history::{
+    get_status_history, record_status_transition, update_job_application_status,
+    NewStatusTransition,
+};
+use crate::db::job_applications::get_job_application_by_order_id;
+use crate::models::webhook::WebhookPayload;
+use crate::state::AppState;
+use crate::utils::redact::redact_pii;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::{error, info};
+
+/// Maps a BPP `fulfillment.state.descriptor.code` onto our `job_applications.status`.
+pub fn map_fulfillment_state(code: &str) -> Option<&'static str> {
+    let normalized = code.trim().to_uppercase().replace([' ', '-'], "_");
+
+    let status = match normalized.as_str() {
+        "APPLIED" | "APPLICATION_SUBMITTED" | "SUBMITTED" => "APPLIED",
+        "UNDER_REVIEW" | "IN_REVIEW" | "REVIEWING" => "UNDER_REVIEW",
+        "SHORTLISTED" => "SHORTLISTED",
+        "INTERVIEW" | "INTERVIEW_SCHEDULED" => "INTERVIEW_SCHEDULED",
+        "OFFERED" | "OFFER_EXTENDED" | "SELECTED" => "OFFERED",
+        "HIRED" | "JOINED" | "ACCEPTED" => "HIRED",
+        "REJECTED" | "NOT_SELECTED" => "REJECTED",
+        "CANCELLED" | "CANCELED" | "WITHDRAWN" => "CANCELLED",
+        _ => return None,
+    };
+
+    Some(status)
+}
+
+pub(crate) fn extract_fulfillment_state_code(order: &Value) -> Option<&str> {
+    let fulfillment = order
+        .get("fulfillments")
+        .and_then(|f| f.get(0))
+        .or_else(|| order.get("fulfillment"))?;
+
+    fulfillment["state"]["descriptor"]["code"].as_str()
+}
+
+/// Updates the application and appends the transition in one transaction.
+/// Returns `Ok(None)` when no open application from this BPP matched.
+async fn apply_status_update(
+    app_state: &AppState,
+    bpp_id: Option<&str>,
+    mapped: Option<&str>,
+    transition: NewStatusTransition<'_>,
+) -> Result<Option<bool>, sqlx::Error> {
+    let mut tx = app_state.db_pool.begin().await?;
+
+    if !update_job_application_status(&mut *tx, transition.order_id, bpp_id, mapped).await? {
+        return Ok(None);
+    }
+    let recorded = record_status_transition(&mut *tx, transition).await?;
+
+    tx.commit().await?;
+    Ok(Some(recorded))
+}
+
+/// Persists the application state carried by an `on_status`/`on_update` callback,
+/// whether or not a caller is waiting on it.
+pub async fn record_status_update(app_state: &AppState, action: &str, payload: &WebhookPayload) {
+    let order = &payload.message["order"];
+
+    let Some(order_id) = order["id"].as_str() else {
+        info!(
+            "⚠️ {} without order.id, skipping status persistence",
+            action
+        );
+        return;
+    };
+
+    let Some(code) = extract_fulfillment_state_code(order) else {
+        info!(
+            "⚠️ {} for order {} has no fulfillment state code",
+            action, order_id
+        );
+        return;
+    };
+
+    let mapped = map_fulfillment_state(code);
+    let status = mapped
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| code.trim().to_uppercase());
+
+    let payload_value = serde_json::to_value(payload).ok().map(|v| redact_pii(&v));
+
+    // Unknown codes stay in the timeline but don't overwrite the application status.
+    if mapped.is_none() {
+        info!(
+            "⚠️ Unmapped fulfillment state '{}' for order {}",
+            code, order_id
+        );
+    }
+
+    match apply_status_update(
+        app_state,
+        payload.context.bpp_id.as_deref(),
+        mapped,
+        NewStatusTransition {
+            order_id,
+            transaction_id: &payload.context.transaction_id,
+            action,
+            status: &status,
+            fulfillment_state_code: Some(code),
+            payload: payload_value.as_ref(),
+        },
+    )
+    .await
+    {
+        Ok(Some(true)) => info!("✅ Order {} moved to {} ({})", order_id, status, code),
+        Ok(Some(false)) => {}
+        Ok(None) => info!(
+            "⚠️ {} for order {} skipped: unknown, closed or held by another BPP",
+            action, order_id
+        ),
+        Err(e) => error!("❌ Failed to update status for order {}: {:?}", order_id, e),
+    }
+}
+
+pub async fn handle_application_timeline(
+    Path(order_id): Path<String>,
+    State(app_state): State<Arc<AppState>>,
+) -> Response {
+    let application = match get_job_application_by_order_id(&app_state.db_pool, &order_id).await {
+        Ok(Some(app)) => app,
+        Ok(None) => {
+            let message = format!("No application found for order_id {}", order_id);
+            return (StatusCode::NOT_FOUND, Json(json!({ "error": message }))).into_response();
+        }
+        Err(e) => {
+            error!("❌ DB error fetching application {}: {:?}", order_id, e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "Database error" })),
+            )
+                .into_response();
+        }
+    };
+
+    match get_status_history(&app_state.db_pool, &order_id).await {
+        Ok(history) => Json(json!({
+            "order_id": order_id,
+            "job_id": application.job_id,
+            "status": application.status,
+            "timeline": history
+        }))
+        .into_response(),
+        Err(e) => {
+            error!("❌ Failed to fetch timeline for {}: {:?}", order_id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "Failed to fetch application timeline" })),
+            )
+                .into_response()
+        }
+    }
+}
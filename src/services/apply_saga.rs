@@ -0,0 +1,242 @@
+use crate::db::job_application_status_history::{record_status_transition, NewStatusTransition};
+use crate::db::job_applications::{store_job_applications, NewJobApplication};
+use crate::db::job_apply_sagas::{
+    get_saga, mark_confirmed, mark_failed, mark_init_acked, JobApplySaga, SAGA_CONFIRMED,
+};
+use crate::models::job_apply::JobApplyRequest;
+use crate::models::webhook::WebhookPayload;
+use crate::services::payload_generator::build_beckn_payload;
+use crate::state::AppState;
+use crate::utils::http_client::post_beckn_json;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::{error, info};
+
+/// Sends an `init`/`confirm` for the saga's request without waiting for the callback.
+pub async fn send_apply_action(
+    app_state: &AppState,
+    req: &JobApplyRequest,
+    action: &str,
+    message_id: &str,
+) -> anyhow::Result<()> {
+    let config = &app_state.config;
+    let adapter_url = format!("{}/{}", config.bap.caller_uri, action);
+
+    let payload = build_beckn_payload(
+        config,
+        &req.context.transaction_id,
+        message_id,
+        &req.message,
+        action,
+        Some(&req.context.bpp_id),
+        Some(&req.context.bpp_uri),
+    );
+
+    post_beckn_json(app_state, &adapter_url, payload).await?;
+    Ok(())
+}
+
+/// Response for an apply that is still in flight or has already finished.
+pub fn saga_response(saga: &JobApplySaga) -> (StatusCode, Value) {
+    if saga.state == SAGA_CONFIRMED {
+        if let Some(on_confirm) = &saga.on_confirm {
+            return (StatusCode::OK, on_confirm.clone());
+        }
+    }
+
+    (
+        StatusCode::ACCEPTED,
+        json!({
+            "transaction_id": saga.transaction_id,
+            "state": saga.state,
+            "last_error": saga.last_error,
+            "poll_url": format!("/api/v1/apply/{}", saga.transaction_id)
+        }),
+    )
+}
+
+pub async fn saga_snapshot(
+    app_state: &AppState,
+    transaction_id: &str,
+) -> Result<(StatusCode, Value), (StatusCode, Value)> {
+    match get_saga(&app_state.db_pool, transaction_id).await {
+        Ok(Some(saga)) => Ok(saga_response(&saga)),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            json!({"error": "Application saga not found"}),
+        )),
+        Err(e) => {
+            error!("❌ Failed to load apply saga {}: {:?}", transaction_id, e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                json!({"error": "Database error"}),
+            ))
+        }
+    }
+}
+
+/// The BPP's reason when a callback reports an error instead of a result.
+pub fn callback_error(payload: &Value) -> Option<String> {
+    let err = payload.get("error").filter(|e| !e.is_null())?;
+    let message = err["message"]
+        .as_str()
+        .or_else(|| err["code"].as_str())
+        .unwrap_or("BPP returned an error");
+    Some(message.to_string())
+}
+
+fn confirmed_application(on_confirm: &Value, order_id: &str) -> NewJobApplication {
+    let order = &on_confirm["message"]["order"];
+    let context_field = |key: &str| {
+        on_confirm["context"][key]
+            .as_str()
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    let user_id = order["fulfillments"]
+        .get(0)
+        .and_then(|f| f["customer"]["person"]["id"].as_str())
+        .map(|s| s.to_string())
+        .or_else(|| {
+            order["fulfillments"]
+                .get(0)
+                .and_then(|f| f["id"].as_str())
+                .map(|s| s.to_string())
+        })
+        .unwrap_or_default();
+
+    let job_id = order["items"]
+        .get(0)
+        .and_then(|i| i["id"].as_str())
+        .unwrap_or_default()
+        .to_string();
+
+    NewJobApplication {
+        user_id,
+        job_id,
+        order_id: order_id.to_string(),
+        transaction_id: context_field("transaction_id"),
+        bpp_id: context_field("bpp_id"),
+        bpp_uri: context_field("bpp_uri"),
+        status: Some("APPLIED".to_string()),
+        metadata: Some(on_confirm.clone()),
+    }
+}
+
+/// Confirms the saga and inserts the application in one transaction, so the
+/// saga is never CONFIRMED without its application. False if already confirmed.
+async fn confirm_saga(
+    app_state: &AppState,
+    transaction_id: &str,
+    order_id: &str,
+    on_confirm: &Value,
+) -> Result<bool, sqlx::Error> {
+    let mut tx = app_state.db_pool.begin().await?;
+
+    if !mark_confirmed(&mut *tx, transaction_id, order_id, on_confirm).await? {
+        return Ok(false);
+    }
+    store_job_applications(&mut *tx, confirmed_application(on_confirm, order_id)).await?;
+
+    tx.commit().await?;
+    Ok(true)
+}
+
+async fn fail_saga(app_state: &AppState, transaction_id: &str, reason: &str) {
+    info!("⚠️ Apply saga {} failed: {}", transaction_id, reason);
+    if let Err(e) = mark_failed(&app_state.db_pool, transaction_id, reason).await {
+        error!(
+            "❌ Failed to mark apply saga {} failed: {:?}",
+            transaction_id, e
+        );
+    }
+}
+
+/// Records an `on_init` against its saga, regardless of whether anyone is still waiting.
+/// An `on_init` carrying an error fails the saga instead of advancing it.
+pub async fn record_on_init(app_state: &AppState, payload: &WebhookPayload) {
+    let txn_id = &payload.context.transaction_id;
+
+    let Ok(on_init) = serde_json::to_value(payload) else {
+        return;
+    };
+
+    if let Some(reason) = callback_error(&on_init) {
+        return fail_saga(app_state, txn_id, &reason).await;
+    }
+    if !on_init["message"]["order"].is_object() {
+        return fail_saga(app_state, txn_id, "on_init carries no order").await;
+    }
+
+    match mark_init_acked(&app_state.db_pool, txn_id, &on_init).await {
+        Ok(true) => info!("✅ Apply saga {} moved to INIT_ACKED", txn_id),
+        Ok(false) => {}
+        Err(e) => error!("❌ Failed to update apply saga {}: {:?}", txn_id, e),
+    }
+}
+
+/// Completes the saga on `on_confirm` and stores the application exactly once,
+/// including callbacks that arrive after the HTTP request gave up.
+pub async fn record_on_confirm(app_state: &AppState, payload: &WebhookPayload) {
+    let txn_id = &payload.context.transaction_id;
+
+    let Ok(on_confirm) = serde_json::to_value(payload) else {
+        return;
+    };
+
+    if let Some(reason) = callback_error(&on_confirm) {
+        return fail_saga(app_state, txn_id, &reason).await;
+    }
+    let order_id = match on_confirm["message"]["order"]["id"].as_str() {
+        Some(id) if !id.trim().is_empty() => id.to_string(),
+        _ => return fail_saga(app_state, txn_id, "on_confirm carries no order id").await,
+    };
+
+    match confirm_saga(app_state, txn_id, &order_id, &on_confirm).await {
+        Ok(true) => {}
+        Ok(false) => return,
+        Err(e) => {
+            // The saga stays open, so a redelivered on_confirm can still complete it.
+            error!("❌ Failed to complete apply saga {}: {:?}", txn_id, e);
+            return;
+        }
+    }
+
+    if let Err(e) = record_status_transition(
+        &app_state.db_pool,
+        NewStatusTransition {
+            order_id: &order_id,
+            transaction_id: txn_id,
+            action: "on_confirm",
+            status: "APPLIED",
+            fulfillment_state_code: None,
+            payload: None,
+        },
+    )
+    .await
+    {
+        error!("❌ Failed to record application status history: {:?}", e);
+    }
+
+    info!(
+        "✅ Apply saga {} confirmed, order_id = {}",
+        txn_id, order_id
+    );
+}
+
+pub async fn handle_apply_status(
+    Path(transaction_id): Path<String>,
+    State(app_state): State<Arc<AppState>>,
+) -> Response {
+    match saga_snapshot(&app_state, &transaction_id).await {
+        Ok((status, body)) => (status, Json(body)).into_response(),
+        Err((status, body)) => (status, Json(body)).into_response(),
+    }
+}
@@ -0,0 +1,358 @@
+use crate::db::job_application_status_history::{record_status_transition, NewStatusTransition};
+use crate::db::job_applications::{
+    clear_cancel_request, get_job_application_by_order_id, mark_job_application_cancelled,
+    record_cancel_request,
+};
+use crate::models::cancel::{CancelApplicationRequest, CancelDescriptor, CancelMessage};
+use crate::models::webhook::{Ack, AckResponse, AckStatus, WebhookPayload};
+use crate::services::application_status::{extract_fulfillment_state_code, map_fulfillment_state};
+use crate::services::payload_generator::build_beckn_payload;
+use crate::state::AppState;
+use crate::utils::correlation::{
+    cancel_waiter, correlation_key, publish_response, register_waiter, wait_for_response, WaitError,
+};
+use crate::utils::http_client::post_beckn_json;
+use crate::utils::redact::redact_pii;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::time::Duration;
+use tracing::{error, info};
+use uuid::Uuid;
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+fn error_response(status: StatusCode, error: impl Into<String>) -> Response {
+    (
+        status,
+        Json(ErrorResponse {
+            error: error.into(),
+        }),
+    )
+        .into_response()
+}
+
+/// Why an on_cancel doesn't confirm the cancellation, or `None` if it does.
+fn cancel_rejection(on_cancel: &Value) -> Option<String> {
+    if let Some(err) = on_cancel.get("error").filter(|e| !e.is_null()) {
+        let message = err["message"]
+            .as_str()
+            .or_else(|| err["code"].as_str())
+            .unwrap_or("BPP returned an error");
+        return Some(message.to_string());
+    }
+
+    let order = &on_cancel["message"]["order"];
+    let state = order["status"]
+        .as_str()
+        .or_else(|| extract_fulfillment_state_code(order));
+
+    match state {
+        Some(s) if map_fulfillment_state(s) == Some("CANCELLED") => None,
+        Some(s) => Some(format!("BPP reported order status '{}'", s)),
+        None => Some("on_cancel carries no order status".to_string()),
+    }
+}
+
+/// Applies a confirmed on_cancel. The callback's details win over those
+/// stored with the request. The callback's `context.bpp_id` and `transaction_id` must
+/// match the application, so no other BPP or transaction can cancel it.
+/// Returns false if nothing matched or the application was already cancelled.
+async fn apply_cancellation(
+    app_state: &AppState,
+    order_id: &str,
+    on_cancel: &Value,
+) -> Result<bool, sqlx::Error> {
+    let cancellation = &on_cancel["message"]["order"]["cancellation"];
+    let transaction_id = on_cancel["context"]["transaction_id"]
+        .as_str()
+        .unwrap_or_default();
+
+    let changed = mark_job_application_cancelled(
+        &app_state.db_pool,
+        order_id,
+        on_cancel["context"]["bpp_id"].as_str(),
+        transaction_id,
+        cancellation["cancelled_by"].as_str(),
+        cancellation["reason"]["id"].as_str(),
+        cancellation["reason"]["descriptor"]["short_desc"].as_str(),
+    )
+    .await?;
+
+    if changed {
+        let payload = redact_pii(on_cancel);
+        if let Err(e) = record_status_transition(
+            &app_state.db_pool,
+            NewStatusTransition {
+                order_id,
+                transaction_id,
+                action: "on_cancel",
+                status: "CANCELLED",
+                fulfillment_state_code: None,
+                payload: Some(&payload),
+            },
+        )
+        .await
+        {
+            error!(
+                "❌ Failed to record cancellation history for {}: {:?}",
+                order_id, e
+            );
+        }
+    }
+
+    Ok(changed)
+}
+
+/// Forgets the pending request's details once the BPP rejects the cancel or
+/// never answers, so a later cancellation isn't attributed to it.
+async fn discard_cancel_request(
+    app_state: &AppState,
+    order_id: &str,
+    bpp_id: Option<&str>,
+    transaction_id: &str,
+) {
+    if let Err(e) = clear_cancel_request(&app_state.db_pool, order_id, bpp_id, transaction_id).await
+    {
+        error!(
+            "❌ Failed to clear cancel request for {}: {:?}",
+            order_id, e
+        );
+    }
+}
+
+pub async fn handle_cancel_application(
+    Path(order_id): Path<String>,
+    State(app_state): State<Arc<AppState>>,
+    Json(req): Json<CancelApplicationRequest>,
+) -> Result<impl IntoResponse, Response> {
+    if req.cancelled_by.trim().is_empty() || req.reason_code.trim().is_empty() {
+        return Err(error_response(
+            StatusCode::BAD_REQUEST,
+            "cancelled_by and reason_code are required",
+        ));
+    }
+
+    let application = match get_job_application_by_order_id(&app_state.db_pool, &order_id).await {
+        Ok(Some(app)) => app,
+        Ok(None) => {
+            return Err(error_response(
+                StatusCode::NOT_FOUND,
+                format!("No application found for order_id {}", order_id),
+            ))
+        }
+        Err(e) => {
+            error!("❌ DB error fetching application {}: {:?}", order_id, e);
+            return Err(error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Database error",
+            ));
+        }
+    };
+
+    if application.status.as_deref() == Some("CANCELLED") {
+        return Err(error_response(
+            StatusCode::CONFLICT,
+            "Application is already cancelled",
+        ));
+    }
+
+    if let Err(e) = record_cancel_request(
+        &app_state.db_pool,
+        &order_id,
+        &req.cancelled_by,
+        &req.reason_code,
+        req.reason.as_deref(),
+    )
+    .await
+    {
+        error!(
+            "❌ Failed to store cancel request for {}: {:?}",
+            order_id, e
+        );
+        return Err(error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to update job application",
+        ));
+    }
+
+    let message_id = format!("msg-{}", Uuid::new_v4());
+    let unique_key = correlation_key(&application.transaction_id, &message_id);
+    let rx = register_waiter(&app_state, &unique_key);
+
+    let config = app_state.config.clone();
+    let adapter_url = format!("{}/cancel", config.bap.caller_uri);
+
+    let message = CancelMessage {
+        order_id: order_id.clone(),
+        cancellation_reason_id: req.reason_code.clone(),
+        descriptor: req.reason.as_ref().map(|r| CancelDescriptor {
+            short_desc: r.clone(),
+        }),
+    };
+
+    let payload = build_beckn_payload(
+        &config,
+        &application.transaction_id,
+        &message_id,
+        &message,
+        "cancel",
+        Some(&application.bpp_id),
+        Some(&application.bpp_uri),
+    );
+
+    let discard = || {
+        discard_cancel_request(
+            &app_state,
+            &order_id,
+            Some(&application.bpp_id),
+            &application.transaction_id,
+        )
+    };
+
+    if let Err(e) = post_beckn_json(&app_state, &adapter_url, payload).await {
+        cancel_waiter(&app_state, &unique_key);
+        discard().await;
+        return Err(error_response(
+            StatusCode::BAD_GATEWAY,
+            format!("Error calling BAP adapter: {}", e),
+        ));
+    }
+
+    let on_cancel =
+        match wait_for_response(&app_state, &unique_key, rx, Duration::from_secs(10)).await {
+            Ok(payload) => payload,
+            Err(WaitError::ChannelClosed) => {
+                discard().await;
+                return Err(error_response(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Internal channel error receiving on_cancel",
+                ));
+            }
+            Err(WaitError::Timeout) => {
+                discard().await;
+                return Err(error_response(
+                    StatusCode::GATEWAY_TIMEOUT,
+                    "Timeout waiting for on_cancel response",
+                ));
+            }
+        };
+
+    if let Some(reason) = cancel_rejection(&on_cancel) {
+        info!("⚠️ Cancellation of {} rejected: {}", order_id, reason);
+        discard().await;
+        return Ok((
+            StatusCode::CONFLICT,
+            Json(json!({
+                "order_id": order_id,
+                "status": application.status,
+                "error": format!("Cancellation rejected: {}", reason),
+                "on_cancel": on_cancel
+            })),
+        ));
+    }
+
+    // The webhook handler usually got here first; this is a no-op then.
+    match apply_cancellation(&app_state, &order_id, &on_cancel).await {
+        Ok(true) => {}
+        Ok(false) => {
+            let cancelled = get_job_application_by_order_id(&app_state.db_pool, &order_id)
+                .await
+                .ok()
+                .flatten()
+                .is_some_and(|app| app.status.as_deref() == Some("CANCELLED"));
+            if !cancelled {
+                info!(
+                    "⚠️ on_cancel for {} does not match its BPP or transaction",
+                    order_id
+                );
+                return Err(error_response(
+                    StatusCode::BAD_GATEWAY,
+                    "on_cancel did not come from the application's BPP and transaction",
+                ));
+            }
+        }
+        Err(e) => {
+            error!(
+                "❌ Failed to mark application {} cancelled: {:?}",
+                order_id, e
+            );
+            return Err(error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to update job application",
+            ));
+        }
+    }
+
+    info!(
+        "✅ Application {} cancelled by {} (reason_code = {})",
+        order_id, req.cancelled_by, req.reason_code
+    );
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "order_id": order_id,
+            "status": "CANCELLED",
+            "on_cancel": on_cancel
+        })),
+    ))
+}
+
+/// Applies the cancellation whether or not a caller is still waiting, so an
+/// on_cancel arriving after the request timed out still lands.
+pub async fn handle_on_cancel(
+    app_state: &AppState,
+    payload: &WebhookPayload,
+    txn_id: &str,
+    msg_id: &str,
+) -> impl IntoResponse {
+    let on_cancel = serde_json::to_value(payload).unwrap_or_default();
+
+    match on_cancel["message"]["order"]["id"].as_str() {
+        Some(order_id) => match cancel_rejection(&on_cancel) {
+            None => match apply_cancellation(app_state, order_id, &on_cancel).await {
+                Ok(true) => info!("✅ Order {} cancelled by on_cancel", order_id),
+                Ok(false) => info!(
+                    "⚠️ on_cancel for order {} not applied: already cancelled, or not from its BPP and transaction",
+                    order_id
+                ),
+                Err(e) => error!(
+                    "❌ Failed to apply on_cancel for order {}: {:?}",
+                    order_id, e
+                ),
+            },
+            Some(reason) => {
+                info!(
+                    "⚠️ on_cancel for order {} not applied: {}",
+                    order_id, reason
+                );
+                discard_cancel_request(
+                    app_state,
+                    order_id,
+                    payload.context.bpp_id.as_deref(),
+                    txn_id,
+                )
+                .await;
+            }
+        },
+        None => info!("⚠️ on_cancel without order.id, transaction_id = {}", txn_id),
+    }
+
+    publish_response(app_state, "on_cancel", payload, txn_id, msg_id).await;
+
+    Json(AckResponse {
+        message: AckStatus {
+            ack: Ack { status: "ACK" },
+        },
+    })
+}
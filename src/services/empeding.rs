@@ -1,90 +1,398 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
 use hex;
 use redis::AsyncCommands;
 use reqwest;
+use reqwest::StatusCode;
 use serde_json::Value;
 use sha2::{Digest, Sha256};
-use tracing::{error, info};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
 
-use crate::state::AppState;
+use crate::config::{AppConfig, EmbeddingProvider};
+use crate::services::local_embedding::LocalEmbeddingService;
 
-#[async_trait]
-pub trait EmbeddingService {
-    async fn get_embedding(
-        &self,
-        text: &str,
-        conn: &mut redis::aio::MultiplexedConnection,
-        app_state: &AppState,
-    ) -> Result<Vec<f32>>;
+const GEMINI_MAX_BATCH: usize = 100;
+const OPENAI_MAX_BATCH: usize = 2048;
+
+/// Returned by a provider when it answers 429, so callers can back off and retry.
+#[derive(Debug)]
+pub struct RateLimited {
+    pub retry_after: Option<Duration>,
+}
+
+impl std::fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "embedding provider rate limited the request")
+    }
+}
+
+impl std::error::Error for RateLimited {}
+
+fn rate_limited(resp: &reqwest::Response) -> RateLimited {
+    let retry_after = resp
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(Duration::from_secs);
+
+    RateLimited { retry_after }
+}
+
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+    pub batch_size: usize,
+    pub concurrency: usize,
+    pub max_retries: u32,
 }
 
-pub struct GcpEmbeddingService;
+type BatchFuture<'a> =
+    Pin<Box<dyn Future<Output = (&'a [String], Result<Vec<Vec<f32>>>)> + Send + 'a>>;
+
+fn cache_key(model: &str, text: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(text.as_bytes());
+    format!("embedding:{}:{}", model, hex::encode(hasher.finalize()))
+}
 
 #[async_trait]
-impl EmbeddingService for GcpEmbeddingService {
+pub trait EmbeddingService: Send + Sync {
+    /// Model name, used to namespace cached vectors.
+    fn model(&self) -> &str;
+
+    fn dimension(&self) -> usize;
+
+    fn batch_options(&self) -> &BatchOptions;
+
+    /// Whether vectors are worth caching in Redis (false for cheap local models).
+    fn cacheable(&self) -> bool {
+        true
+    }
+
+    /// Calls the provider without touching the cache.
+    async fn embed(&self, text: &str) -> Result<Vec<f32>>;
+
+    /// Calls the provider once for several texts; providers without a batch
+    /// endpoint fall back to one call per text.
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut out = Vec::with_capacity(texts.len());
+        for text in texts {
+            out.push(self.embed(text).await?);
+        }
+        Ok(out)
+    }
+
+    /// `embed_batch` with exponential backoff on 429 and a dimension check.
+    async fn embed_batch_with_retry(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let max_retries = self.batch_options().max_retries;
+        let mut attempt = 0;
+
+        loop {
+            match self.embed_batch(texts).await {
+                Ok(vectors) => {
+                    if vectors.len() != texts.len() {
+                        return Err(anyhow::anyhow!(
+                            "Embedding model {} returned {} vectors for {} inputs",
+                            self.model(),
+                            vectors.len(),
+                            texts.len()
+                        ));
+                    }
+                    if let Some(v) = vectors.iter().find(|v| v.len() != self.dimension()) {
+                        return Err(anyhow::anyhow!(
+                            "Embedding model {} returned {} dims, expected {}",
+                            self.model(),
+                            v.len(),
+                            self.dimension()
+                        ));
+                    }
+                    return Ok(vectors);
+                }
+                Err(e) => {
+                    let Some(limited) = e.downcast_ref::<RateLimited>() else {
+                        return Err(e);
+                    };
+                    if attempt >= max_retries {
+                        return Err(e);
+                    }
+
+                    let backoff = limited
+                        .retry_after
+                        .unwrap_or_else(|| Duration::from_millis(500 * 2u64.pow(attempt)));
+                    attempt += 1;
+
+                    warn!(
+                        "⏳ Embedding provider rate limited, retry {}/{} in {:?}",
+                        attempt, max_retries, backoff
+                    );
+                    tokio::time::sleep(backoff).await;
+                }
+            }
+        }
+    }
+
     async fn get_embedding(
         &self,
         text: &str,
         conn: &mut redis::aio::MultiplexedConnection,
-        app_state: &AppState,
     ) -> Result<Vec<f32>> {
         if text.trim().is_empty() {
-            // info!("⚠️ Skipping embedding generation: input text is empty");
             return Ok(vec![]);
         }
-        // let start = std::time::Instant::now();
-        let mut hasher = Sha256::new();
-        hasher.update(text.as_bytes());
-        let hash = hex::encode(hasher.finalize());
 
-        let cache_key = format!("embedding:{}:{}", app_state.config.gcp.model, hash);
+        if !self.cacheable() {
+            return self.embed(text).await;
+        }
 
-        // info!(
-        //     "🔑 Embedding request | chars={} | cache_key={}",
-        //     text.len(),
-        //     cache_key
-        // );
+        let cache_key = cache_key(self.model(), text);
 
         match conn.get::<_, Option<String>>(&cache_key).await {
             Ok(Some(cached)) => {
                 if let Ok(vec) = serde_json::from_str::<Vec<f32>>(&cached) {
-                    // info!("✅ Embedding cache hit");
-                    return Ok(vec);
-                } else {
-                    // info!("⚠️ Failed to deserialize cached embedding, refetching");
+                    if vec.len() == self.dimension() {
+                        return Ok(vec);
+                    }
                 }
             }
             Ok(None) => info!("❌ Embedding cache miss"),
             Err(e) => error!("❌ Redis get error: {:?}", e),
         }
 
-        let url = format!(
-            "https://generativelanguage.googleapis.com/v1beta/models/{}:embedContent",
-            app_state.config.gcp.model
+        let embedding = self
+            .embed_batch_with_retry(&[text.to_string()])
+            .await?
+            .pop()
+            .unwrap_or_default();
+
+        if let Err(e) = conn
+            .set::<_, _, ()>(&cache_key, serde_json::to_string(&embedding)?)
+            .await
+        {
+            error!("❌ Failed to cache embedding in Redis: {:?}", e);
+        }
+
+        Ok(embedding)
+    }
+
+    /// Embeds many texts at once: cache hits come from a single MGET, misses
+    /// are deduplicated and sent in provider-sized batches, a bounded number
+    /// in parallel. The result is aligned with `texts`; an entry is `None`
+    /// when its text was empty or its batch failed (failures are logged).
+    async fn get_embeddings(
+        &self,
+        texts: &[&str],
+        conn: &mut redis::aio::MultiplexedConnection,
+    ) -> Vec<Option<Vec<f32>>> {
+        let mut results: Vec<Option<Vec<f32>>> = vec![None; texts.len()];
+
+        let wanted: Vec<usize> = (0..texts.len())
+            .filter(|&i| !texts[i].trim().is_empty())
+            .collect();
+
+        if wanted.is_empty() {
+            return results;
+        }
+
+        let keys: Vec<String> = wanted
+            .iter()
+            .map(|&i| cache_key(self.model(), texts[i]))
+            .collect();
+
+        if self.cacheable() {
+            match redis::cmd("MGET")
+                .arg(&keys)
+                .query_async::<Vec<Option<String>>>(&mut *conn)
+                .await
+            {
+                Ok(cached) => {
+                    for (&i, value) in wanted.iter().zip(cached) {
+                        let vec = value.and_then(|v| serde_json::from_str::<Vec<f32>>(&v).ok());
+                        if let Some(vec) = vec.filter(|v| v.len() == self.dimension()) {
+                            results[i] = Some(vec);
+                        }
+                    }
+                }
+                Err(e) => error!("❌ Redis MGET error: {:?}", e),
+            }
+        }
+
+        // Unique texts still missing, mapped to their cache keys.
+        let mut misses: HashMap<&str, &str> = HashMap::new();
+        for (&i, key) in wanted.iter().zip(&keys) {
+            if results[i].is_none() {
+                misses.insert(texts[i], key.as_str());
+            }
+        }
+
+        if misses.is_empty() {
+            return results;
+        }
+
+        let options = self.batch_options();
+        let miss_texts: Vec<String> = misses.keys().map(|t| t.to_string()).collect();
+        let batches: Vec<&[String]> = miss_texts.chunks(options.batch_size.max(1)).collect();
+
+        info!(
+            "🧮 Embedding {} of {} texts in {} batches ({} cached)",
+            miss_texts.len(),
+            wanted.len(),
+            batches.len(),
+            wanted.len() - miss_texts.len()
         );
 
-        let body = serde_json::json!({
-            "model": format!("models/{}", app_state.config.gcp.model),
-            "content": {
-                "parts": [
-                    { "text": text }
-                ]
+        let mut requests: Vec<BatchFuture<'_>> = Vec::with_capacity(batches.len());
+        for batch in batches {
+            requests.push(Box::pin(async move {
+                (batch, self.embed_batch_with_retry(batch).await)
+            }));
+        }
+
+        let fetched: Vec<(&[String], Result<Vec<Vec<f32>>>)> = stream::iter(requests)
+            .buffer_unordered(options.concurrency.max(1))
+            .collect()
+            .await;
+
+        let mut computed: HashMap<&str, Vec<f32>> = HashMap::new();
+        for (batch, outcome) in fetched {
+            match outcome {
+                Ok(vectors) => {
+                    for (text, vector) in batch.iter().zip(vectors) {
+                        computed.insert(text.as_str(), vector);
+                    }
+                }
+                Err(e) => error!(
+                    "❌ Embedding batch of {} texts failed: {:?}",
+                    batch.len(),
+                    e
+                ),
             }
-        });
+        }
+
+        if self.cacheable() && !computed.is_empty() {
+            let mut pipe = redis::pipe();
+            for (text, vector) in &computed {
+                if let (Some(key), Ok(json)) = (misses.get(text), serde_json::to_string(vector)) {
+                    pipe.set(*key, json).ignore();
+                }
+            }
+            if let Err(e) = pipe.query_async::<()>(&mut *conn).await {
+                error!("❌ Failed to cache embeddings in Redis: {:?}", e);
+            }
+        }
 
-        // info!("🚀 Fetching embedding from GCP");
+        for &i in &wanted {
+            if results[i].is_none() {
+                results[i] = computed.get(texts[i]).cloned();
+            }
+        }
 
-        let client = reqwest::Client::new();
-        let resp = client
+        results
+    }
+}
+
+/// Builds the provider selected in `embedding.provider`, sharing one HTTP client.
+pub fn build_embedding_service(config: &AppConfig) -> Result<Arc<dyn EmbeddingService>> {
+    let embedding = &config.embedding;
+    let dimension = embedding.dimension as usize;
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(embedding.timeout_secs))
+        .build()?;
+
+    let batch = |provider_max: usize| BatchOptions {
+        batch_size: embedding.batch_size.clamp(1, provider_max),
+        concurrency: embedding.concurrency.max(1),
+        max_retries: embedding.max_retries,
+    };
+
+    let service: Arc<dyn EmbeddingService> = match embedding.provider {
+        EmbeddingProvider::Gemini => {
+            let api_key = embedding
+                .api_key
+                .clone()
+                .or_else(|| config.gcp.as_ref().map(|gcp| gcp.auth_token.clone()))
+                .ok_or_else(|| {
+                    anyhow::anyhow!("Gemini embeddings need embedding.api_key or gcp.auth_token")
+                })?;
+
+            Arc::new(GcpEmbeddingService {
+                client,
+                model: embedding.model.clone(),
+                api_key,
+                dimension,
+                batch: batch(GEMINI_MAX_BATCH),
+            })
+        }
+        EmbeddingProvider::OpenAi => {
+            let base_url = embedding
+                .base_url
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("OpenAI embeddings need embedding.base_url"))?;
+
+            Arc::new(OpenAiEmbeddingService {
+                client,
+                base_url: base_url.trim_end_matches('/').to_string(),
+                model: embedding.model.clone(),
+                api_key: embedding.api_key.clone(),
+                dimension,
+                batch: batch(OPENAI_MAX_BATCH),
+            })
+        }
+        EmbeddingProvider::Local => Arc::new(LocalEmbeddingService::new(
+            embedding.model.clone(),
+            dimension,
+            batch(usize::MAX),
+        )),
+    };
+
+    info!(
+        "✅ Embedding provider {:?} ({}, {} dims)",
+        embedding.provider, embedding.model, dimension
+    );
+
+    Ok(service)
+}
+
+fn parse_vector(values: &[Value]) -> Vec<f32> {
+    values
+        .iter()
+        .map(|v| v.as_f64().unwrap_or(0.0) as f32)
+        .collect()
+}
+
+pub struct GcpEmbeddingService {
+    client: reqwest::Client,
+    model: String,
+    api_key: String,
+    dimension: usize,
+    batch: BatchOptions,
+}
+
+impl GcpEmbeddingService {
+    async fn post(&self, method: &str, body: Value) -> Result<Value> {
+        let url = format!(
+            "https://generativelanguage.googleapis.com/v1beta/models/{}:{}",
+            self.model, method
+        );
+
+        let resp = self
+            .client
             .post(&url)
-            .header("x-goog-api-key", &app_state.config.gcp.auth_token)
+            .header("x-goog-api-key", &self.api_key)
             .json(&body)
             .send()
             .await?;
 
         let status = resp.status();
-        // info!("📡 GCP embedding response status: {}", status);
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            return Err(rate_limited(&resp).into());
+        }
 
         let json_resp: Value = resp.json().await?;
 
@@ -96,27 +404,151 @@ impl EmbeddingService for GcpEmbeddingService {
             ));
         }
 
+        Ok(json_resp)
+    }
+
+    fn request(&self, text: &str) -> Value {
+        serde_json::json!({
+            "model": format!("models/{}", self.model),
+            "content": {
+                "parts": [
+                    { "text": text }
+                ]
+            },
+            "outputDimensionality": self.dimension
+        })
+    }
+}
+
+#[async_trait]
+impl EmbeddingService for GcpEmbeddingService {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn batch_options(&self) -> &BatchOptions {
+        &self.batch
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let json_resp = self.post("embedContent", self.request(text)).await?;
+
         let embedding_values = json_resp
             .get("embedding")
             .and_then(|e| e.get("values"))
             .and_then(|v| v.as_array())
             .ok_or_else(|| anyhow::anyhow!("Missing embedding.values in GCP response"))?;
 
-        let embedding: Vec<f32> = embedding_values
+        Ok(parse_vector(embedding_values))
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let requests: Vec<Value> = texts.iter().map(|t| self.request(t)).collect();
+
+        let json_resp = self
+            .post(
+                "batchEmbedContents",
+                serde_json::json!({ "requests": requests }),
+            )
+            .await?;
+
+        let embeddings = json_resp
+            .get("embeddings")
+            .and_then(|e| e.as_array())
+            .ok_or_else(|| anyhow::anyhow!("Missing embeddings in GCP batch response"))?;
+
+        embeddings
             .iter()
-            .map(|v| v.as_f64().unwrap_or(0.0) as f32)
-            .collect();
+            .map(|e| {
+                e.get("values")
+                    .and_then(|v| v.as_array())
+                    .map(|v| parse_vector(v))
+                    .ok_or_else(|| anyhow::anyhow!("Missing embeddings[].values in GCP response"))
+            })
+            .collect()
+    }
+}
 
-        if let Err(e) = conn
-            .set::<_, _, ()>(&cache_key, serde_json::to_string(&embedding)?)
-            .await
-        {
-            error!("❌ Failed to cache embedding in Redis: {:?}", e);
-        } else {
-            // info!("💾 Cached embedding | dims={}", embedding.len());
+/// Any server implementing the OpenAI `/v1/embeddings` API (OpenAI, vLLM, Ollama, TEI, ...).
+pub struct OpenAiEmbeddingService {
+    client: reqwest::Client,
+    base_url: String,
+    model: String,
+    api_key: Option<String>,
+    dimension: usize,
+    batch: BatchOptions,
+}
+
+#[async_trait]
+impl EmbeddingService for OpenAiEmbeddingService {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn batch_options(&self) -> &BatchOptions {
+        &self.batch
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        self.embed_batch(&[text.to_string()])
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow::anyhow!("Empty data in OpenAI embedding response"))
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let url = format!("{}/v1/embeddings", self.base_url);
+
+        let body = serde_json::json!({
+            "model": self.model,
+            "input": texts
+        });
+
+        let mut request = self.client.post(&url).json(&body);
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
         }
-        // let duration = start.elapsed();
-        // info!("⏱️ get_embedding took: {:?}", duration);
-        Ok(embedding)
+
+        let resp = request.send().await?;
+        let status = resp.status();
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            return Err(rate_limited(&resp).into());
+        }
+
+        let json_resp: Value = resp.json().await?;
+
+        if !status.is_success() {
+            error!("❌ OpenAI embedding API error response: {:?}", json_resp);
+            return Err(anyhow::anyhow!(
+                "OpenAI embedding API failed with status {}",
+                status
+            ));
+        }
+
+        let data = json_resp["data"]
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("Missing data in OpenAI embedding response"))?;
+
+        // Entries carry their input index and are not guaranteed to be in order.
+        let mut out: Vec<Vec<f32>> = vec![Vec::new(); texts.len()];
+        for (pos, item) in data.iter().enumerate() {
+            let index = item["index"].as_u64().map(|i| i as usize).unwrap_or(pos);
+            let values = item["embedding"]
+                .as_array()
+                .ok_or_else(|| anyhow::anyhow!("Missing data[].embedding in OpenAI response"))?;
+            if let Some(slot) = out.get_mut(index) {
+                *slot = parse_vector(values);
+            }
+        }
+
+        Ok(out)
     }
 }
@@ -1,11 +1,19 @@
 use crate::db::{
     job::{fetch_job_by_job_id, JobLookup},
-    job_applications::{get_job_applications, store_job_applications, NewJobApplication},
+    job_applications::get_job_applications,
+    job_apply_sagas::{
+        claim_step, mark_failed, start_saga, NewJobApplySaga, SAGA_CONFIRM_SENT, SAGA_INIT_ACKED,
+    },
     profiles::{get_or_sync_profile, ProfileLookup},
 };
 use crate::models::webhook::{Ack, AckResponse, AckStatus, WebhookPayload};
-use crate::services::payload_generator::build_beckn_payload;
-use crate::utils::{external_apis::call_google_geocode, http_client::post_json};
+use crate::services::apply_saga::{
+    callback_error, record_on_confirm, record_on_init, saga_snapshot, send_apply_action,
+};
+use crate::utils::correlation::{
+    cancel_waiter, correlation_key, publish_response, register_waiter, wait_for_response, WaitError,
+};
+use crate::utils::external_apis::call_google_geocode;
 use crate::{
     models::job_apply::{JobApplicationsQuery, JobApplyRequest, JobApplyV2Request},
     state::AppState,
@@ -18,9 +26,7 @@ use axum::{
 };
 use serde::Serialize;
 use serde_json::{json, Value};
-use tokio::sync::oneshot::channel;
-use tokio::time::{timeout, Duration};
-use tracing::info;
+use tokio::time::Duration;
 use uuid::Uuid;
 
 use std::sync::Arc;
@@ -34,14 +40,14 @@ pub async fn handle_job_apply(
     Json(req): Json<JobApplyRequest>,
 ) -> Result<impl IntoResponse, Response> {
     match process_job_apply(&app_state, &req).await {
-        Ok(res) => Ok((StatusCode::OK, Json(res))),
+        Ok((status, res)) => Ok((status, Json(res))),
         Err((status, err)) => Err((status, Json(err)).into_response()),
     }
 }
 pub async fn process_job_apply(
     app_state: &Arc<AppState>,
     req: &JobApplyRequest,
-) -> Result<serde_json::Value, (StatusCode, serde_json::Value)> {
+) -> Result<(StatusCode, serde_json::Value), (StatusCode, serde_json::Value)> {
     let user_id = req
         .message
         .order
@@ -73,113 +79,111 @@ pub async fn process_job_apply(
         };
 
     if let Some(application) = existing.into_iter().next() {
-        return Ok(json!({
-            "message": "User has already applied for this job",
-            "application": application
-        }));
+        return Ok((
+            StatusCode::OK,
+            json!({
+                "message": "User has already applied for this job",
+                "application": application
+            }),
+        ));
     }
 
-    let _on_init = match call_and_wait_for_action(app_state, req, "init").await {
-        Ok(data) => data,
-        Err((status, err)) => return Err((status, json!(err.0))),
-    };
-
-    let on_confirm = match call_and_wait_for_action(app_state, req, "confirm").await {
-        Ok(data) => data,
-        Err((status, err)) => return Err((status, json!(err.0))),
-    };
+    let transaction_id = &req.context.transaction_id;
+    let init_message_id = format!("msg-{}", Uuid::new_v4());
+
+    let started = start_saga(
+        &app_state.db_pool,
+        NewJobApplySaga {
+            transaction_id,
+            user_id: &user_id,
+            job_id: &job_id,
+            bpp_id: &req.context.bpp_id,
+            bpp_uri: &req.context.bpp_uri,
+            request: json!(req),
+            message_id: &init_message_id,
+        },
+    )
+    .await;
 
-    let transaction_id = on_confirm["context"]["transaction_id"]
-        .as_str()
-        .unwrap_or_default()
-        .to_string();
+    match started {
+        Ok(true) => {}
+        // Same transaction already in flight or done; report where it stands.
+        Ok(false) => return saga_snapshot(app_state, transaction_id).await,
+        Err(e) => {
+            tracing::error!("❌ Failed to start apply saga: {:?}", e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                json!({"error": "Database error"}),
+            ));
+        }
+    }
 
-    let bpp_id = on_confirm["context"]["bpp_id"]
-        .as_str()
-        .unwrap_or_default()
-        .to_string();
+    if let Err(err) = call_and_wait_for_action(app_state, req, "init", &init_message_id).await {
+        return saga_step_failed(app_state, transaction_id, err).await;
+    }
 
-    let bpp_uri = on_confirm["context"]["bpp_uri"]
-        .as_str()
-        .unwrap_or_default()
-        .to_string();
+    let confirm_message_id = format!("msg-{}", Uuid::new_v4());
+    match claim_step(
+        &app_state.db_pool,
+        transaction_id,
+        SAGA_INIT_ACKED,
+        SAGA_CONFIRM_SENT,
+        &confirm_message_id,
+        None,
+    )
+    .await
+    {
+        Ok(true) => {}
+        Ok(false) => return saga_snapshot(app_state, transaction_id).await,
+        Err(e) => {
+            tracing::error!("❌ Failed to advance apply saga: {:?}", e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                json!({"error": "Database error"}),
+            ));
+        }
+    }
 
-    let user_id = on_confirm["message"]["order"]["fulfillments"]
-        .get(0)
-        .and_then(|f| f["customer"]["person"]["id"].as_str())
-        .map(|s| s.to_string())
-        .or_else(|| {
-            on_confirm["message"]["order"]["fulfillments"]
-                .get(0)
-                .and_then(|f| f["id"].as_str())
-                .map(|s| s.to_string())
-        })
-        .unwrap_or_default();
+    // The on_confirm webhook completes the saga and stores the application;
+    // one carrying an error has already failed it.
+    match call_and_wait_for_action(app_state, req, "confirm", &confirm_message_id).await {
+        Ok(on_confirm) if callback_error(&on_confirm).is_some() => {
+            saga_snapshot(app_state, transaction_id).await
+        }
+        Ok(on_confirm) => Ok((StatusCode::OK, on_confirm)),
+        Err(err) => saga_step_failed(app_state, transaction_id, err).await,
+    }
+}
 
-    let job_id = on_confirm["message"]["order"]["items"]
-        .get(0)
-        .and_then(|i| i["id"].as_str())
-        .unwrap_or_default()
-        .to_string();
-
-    let order_id = on_confirm["message"]["order"]["id"]
-        .as_str()
-        .unwrap_or_default()
-        .to_string();
-
-    let new_application = NewJobApplication {
-        user_id,
-        job_id,
-        order_id,
-        transaction_id,
-        bpp_id,
-        bpp_uri,
-        status: Some("APPLIED".to_string()),
-        metadata: Some(on_confirm.clone()),
-    };
+/// Timeouts leave the saga for the sweeper and tell the client to poll;
+/// anything else fails the saga so the client can retry.
+async fn saga_step_failed(
+    app_state: &AppState,
+    transaction_id: &str,
+    (status, err): (StatusCode, Json<ErrorResponse>),
+) -> Result<(StatusCode, serde_json::Value), (StatusCode, serde_json::Value)> {
+    if status == StatusCode::GATEWAY_TIMEOUT {
+        return saga_snapshot(app_state, transaction_id).await;
+    }
 
-    if let Err(e) = store_job_applications(&app_state.db_pool, new_application).await {
-        tracing::error!("❌ Failed to store job application: {:?}", e);
-        return Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            json!({"error": "Failed to store job application"}),
-        ));
+    if let Err(e) = mark_failed(&app_state.db_pool, transaction_id, &err.error).await {
+        tracing::error!("❌ Failed to mark apply saga failed: {:?}", e);
     }
 
-    Ok(on_confirm)
+    Err((status, json!(err.0)))
 }
 
 async fn call_and_wait_for_action(
     app_state: &AppState,
     req: &JobApplyRequest,
     action: &str,
+    message_id: &str,
 ) -> Result<serde_json::Value, (StatusCode, Json<ErrorResponse>)> {
-    let transaction_id = &req.context.transaction_id;
-    let message_id = format!("msg-{}", Uuid::new_v4());
-    let unique_key = format!("{}:{}", transaction_id, message_id);
-
-    let (tx, rx) = channel();
+    let unique_key = correlation_key(&req.context.transaction_id, message_id);
+    let rx = register_waiter(app_state, &unique_key);
 
-    app_state
-        .shared_state
-        .pending_searches
-        .insert(unique_key.clone(), tx);
-
-    let config = &app_state.config;
-    let adapter_url = format!("{}/{}", config.bap.caller_uri, action);
-
-    let payload = build_beckn_payload(
-        config,
-        transaction_id,
-        &message_id,
-        &req.message,
-        action,
-        Some(&req.context.bpp_id),
-        Some(&req.context.bpp_uri),
-    );
-
-    if let Err(e) = post_json(&adapter_url, payload, None).await {
-        app_state.shared_state.pending_searches.remove(&unique_key);
+    if let Err(e) = send_apply_action(app_state, req, action, message_id).await {
+        cancel_waiter(app_state, &unique_key);
         return Err((
             StatusCode::BAD_GATEWAY,
             Json(ErrorResponse {
@@ -188,23 +192,20 @@ async fn call_and_wait_for_action(
         ));
     }
 
-    match timeout(Duration::from_secs(10), rx).await {
-        Ok(Ok(on_action_payload)) => Ok(on_action_payload),
-        Ok(Err(_recv)) => Err((
+    match wait_for_response(app_state, &unique_key, rx, Duration::from_secs(10)).await {
+        Ok(on_action_payload) => Ok(on_action_payload),
+        Err(WaitError::ChannelClosed) => Err((
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse {
                 error: format!("Internal error receiving on_{} response", action),
             }),
         )),
-        Err(_elapsed) => {
-            app_state.shared_state.pending_searches.remove(&unique_key);
-            Err((
-                StatusCode::GATEWAY_TIMEOUT,
-                Json(ErrorResponse {
-                    error: format!("Timeout waiting for on_{} response", action),
-                }),
-            ))
-        }
+        Err(WaitError::Timeout) => Err((
+            StatusCode::GATEWAY_TIMEOUT,
+            Json(ErrorResponse {
+                error: format!("Timeout waiting for on_{} response", action),
+            }),
+        )),
     }
 }
 
@@ -214,27 +215,8 @@ pub async fn handle_on_init(
     txn_id: &str,
     msg_id: &str,
 ) -> impl IntoResponse {
-    let unique_key = format!("{}:{}", txn_id, msg_id);
-    match app_state.shared_state.pending_searches.remove(&unique_key) {
-        Some((_, sender)) => match serde_json::to_value(payload) {
-            Ok(json_value) => {
-                if let Err(e) = sender.send(json_value) {
-                    info!("⚠️ Failed to send on_init payload: {:?}", e);
-                } else {
-                    info!("✅ Delivered on_init to waiting request");
-                }
-            }
-            Err(e) => {
-                info!("❌ Failed to serialize payload: {:?}", e);
-            }
-        },
-        None => {
-            info!(
-                "⚠️ No pending /init request found for transaction_id = {}",
-                txn_id
-            );
-        }
-    }
+    record_on_init(app_state, payload).await;
+    publish_response(app_state, "on_init", payload, txn_id, msg_id).await;
 
     Json(AckResponse {
         message: AckStatus {
@@ -249,28 +231,8 @@ pub async fn handle_on_confirm(
     txn_id: &str,
     msg_id: &str,
 ) -> impl IntoResponse {
-    let unique_key = format!("{}:{}", txn_id, msg_id);
-
-    match app_state.shared_state.pending_searches.remove(&unique_key) {
-        Some((_, sender)) => match serde_json::to_value(payload) {
-            Ok(json_value) => {
-                if let Err(e) = sender.send(json_value) {
-                    info!("⚠️ Failed to send on_confirm payload: {:?}", e);
-                } else {
-                    info!("✅ Delivered on_confirm to waiting request");
-                }
-            }
-            Err(e) => {
-                info!("❌ Failed to serialize payload: {:?}", e);
-            }
-        },
-        None => {
-            info!(
-                "⚠️ No pending /confirm request found for transaction_id = {}",
-                txn_id
-            );
-        }
-    }
+    record_on_confirm(app_state, payload).await;
+    publish_response(app_state, "on_confirm", payload, txn_id, msg_id).await;
 
     Json(AckResponse {
         message: AckStatus {
@@ -357,13 +319,13 @@ pub async fn handle_job_apply_v2(
         }
     };
 
-    let result = match process_job_apply(&app_state, &job_apply_req).await {
+    let (status, result) = match process_job_apply(&app_state, &job_apply_req).await {
         Ok(res) => res,
         Err((status, err)) => {
             return Err((status, Json(err)).into_response());
         }
     };
-    Ok((StatusCode::OK, Json(result)))
+    Ok((status, Json(result)))
 }
 
 pub async fn build_job_apply_payload(
@@ -0,0 +1,100 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+use crate::services::empeding::{BatchOptions, EmbeddingService};
+
+const NGRAM: usize = 3;
+
+/// Deterministic offline embedder: word unigrams and character trigrams are
+/// feature-hashed (with a sign bit) into `dimension` buckets, weighted by
+/// sublinear term frequency and L2-normalised, so cosine similarity reflects
+/// lexical overlap. Good enough for dev and tests, no network or API key needed.
+pub struct LocalEmbeddingService {
+    model: String,
+    dimension: usize,
+    batch: BatchOptions,
+}
+
+impl LocalEmbeddingService {
+    pub fn new(model: String, dimension: usize, batch: BatchOptions) -> Self {
+        Self {
+            model,
+            dimension,
+            batch,
+        }
+    }
+
+    pub fn embed_text(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0f32; self.dimension];
+        if self.dimension == 0 {
+            return vector;
+        }
+
+        let mut counts: HashMap<String, u32> = HashMap::new();
+
+        for word in text
+            .to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+        {
+            *counts.entry(format!("w:{}", word)).or_default() += 1;
+
+            let padded: Vec<char> = format!(" {} ", word).chars().collect();
+            for gram in padded.windows(NGRAM) {
+                let gram: String = gram.iter().collect();
+                *counts.entry(format!("c:{}", gram)).or_default() += 1;
+            }
+        }
+
+        for (feature, count) in counts {
+            let hash = fnv1a(feature.as_bytes());
+            let bucket = (hash % self.dimension as u64) as usize;
+            let sign = if (hash >> 63) & 1 == 0 { 1.0 } else { -1.0 };
+            vector[bucket] += sign * (1.0 + (count as f32).ln());
+        }
+
+        let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            vector.iter_mut().for_each(|x| *x /= norm);
+        }
+
+        vector
+    }
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[async_trait]
+impl EmbeddingService for LocalEmbeddingService {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn batch_options(&self) -> &BatchOptions {
+        &self.batch
+    }
+
+    fn cacheable(&self) -> bool {
+        false
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        Ok(self.embed_text(text))
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|t| self.embed_text(t)).collect())
+    }
+}
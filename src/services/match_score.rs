@@ -1,3 +1,4 @@
+use crate::db::profiles::batch_update_profile_embeddings;
 use crate::utils::empeding::{compute_empeding_match_score, profile_text_for_embedding};
 use crate::{
     db::{job::JobRow, profiles::ProfileRow},
@@ -5,13 +6,16 @@ use crate::{
 };
 use serde_json::Value;
 use serde_json::{json, Value as JsonValue};
+use tracing::error;
+use uuid::Uuid;
 
-use crate::services::empeding::{EmbeddingService, GcpEmbeddingService};
+/// Scores a pair; `None` when it couldn't be scored (missing vector or a
+/// transient Redis/embedding failure), so callers can retry instead of storing 0.
 pub async fn compute_match_score(
     app_state: &AppState,
     job: &JobRow,
     profile: &ProfileRow,
-) -> (i16, Option<Value>) {
+) -> Option<(i16, Option<Value>)> {
     let source = &app_state.config.cron.compute_match_scores.source;
 
     match source.as_str() {
@@ -20,53 +24,124 @@ pub async fn compute_match_score(
     }
 }
 
-pub async fn compute_match_score_empeding(
-    app_state: &AppState,
-    job: &JobRow,
-    profile: &ProfileRow,
-) -> (i16, Option<JsonValue>) {
-    let embedding_service = GcpEmbeddingService;
-
-    let result: Option<(i16, Option<JsonValue>)> = async {
-        let mut conn = app_state.redis_pool.get().await.ok()?;
-
-        let metadata = profile.metadata.as_ref()?;
-        let profile_meta = json!({ "metadata": metadata });
-
-        let profile_text = profile_text_for_embedding(&profile_meta, &app_state.config);
-        let profile_emb = embedding_service
-            .get_embedding(&profile_text, &mut conn, app_state)
-            .await
-            .ok()?;
-
-        let profile_norm = profile_emb.iter().map(|x| x * x).sum::<f32>().sqrt();
-
-        let job_emb = job.embedding.as_ref()?;
-
-        let job_norm = job_emb.iter().map(|x| x * x).sum::<f32>().sqrt();
-
-        let beckn_structure = job.beckn_structure.as_ref()?;
-
-        let mut string_sim_cache = std::collections::HashMap::new();
+fn profile_meta(profile: &ProfileRow) -> Option<JsonValue> {
+    let metadata = profile.metadata.as_ref()?;
+    Some(json!({ "metadata": metadata }))
+}
 
-        let score = compute_empeding_match_score(
-            &profile_emb,
-            profile_norm,
-            job_emb,
-            job_norm,
-            &profile_meta,
-            beckn_structure,
-            &app_state.config,
-            &mut string_sim_cache,
-        );
+/// Fills in (and persists) vectors for profiles that lack one from the current
+/// model and embed rules, in one batched embedding call. Returns how many were
+/// embedded; an older vector stays in place when its replacement fails.
+pub async fn ensure_profile_embeddings(app_state: &AppState, profiles: &mut [ProfileRow]) -> usize {
+    let model = app_state.embedding.model();
+    let dimension = app_state.embedding.dimension();
+    let rules = app_state.match_rules.current();
+    let rules_hash = rules.embedding_inputs_hash();
+
+    let pending: Vec<(usize, String)> = profiles
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| {
+            p.current_embedding(model, dimension).is_none()
+                || p.embedding_rules_hash.as_deref() != Some(rules_hash.as_str())
+        })
+        .filter_map(|(i, p)| {
+            let meta = profile_meta(p)?;
+            Some((i, profile_text_for_embedding(&meta, &rules)))
+        })
+        .collect();
+
+    if pending.is_empty() {
+        return 0;
+    }
 
-        let score_i16 = (score * 10.0).round() as i16;
+    let Ok(mut conn) = app_state.redis_pool.get().await else {
+        return 0;
+    };
+
+    let texts: Vec<&str> = pending.iter().map(|(_, text)| text.as_str()).collect();
+    let embeddings = app_state.embedding.get_embeddings(&texts, &mut conn).await;
+
+    let mut updates: Vec<(Uuid, Vec<f32>)> = Vec::new();
+    for ((i, _), embedding) in pending.into_iter().zip(embeddings) {
+        let Some(embedding) = embedding else {
+            continue;
+        };
+        let profile = &mut profiles[i];
+        updates.push((profile.id, embedding.clone()));
+        profile.embedding = Some(embedding);
+        profile.embedding_model = Some(model.to_string());
+        profile.embedding_dim = Some(dimension as i32);
+        profile.embedding_rules_hash = Some(rules_hash.clone());
+    }
 
-        Some((score_i16, Some(json!({ "cosine_score": score }))))
+    if let Err(e) = batch_update_profile_embeddings(
+        &app_state.db_pool,
+        &updates,
+        model,
+        dimension as i32,
+        &rules_hash,
+    )
+    .await
+    {
+        error!("❌ Failed to store profile embeddings: {:?}", e);
     }
-    .await;
 
-    result.unwrap_or((0, None))
+    updates.len()
+}
+
+pub async fn compute_match_score_empeding(
+    app_state: &AppState,
+    job: &JobRow,
+    profile: &ProfileRow,
+) -> Option<(i16, Option<JsonValue>)> {
+    let profile_meta = profile_meta(profile)?;
+    // One snapshot, so a reload mid-pair can't mix two rule sets.
+    let rules = app_state.match_rules.current();
+
+    let stored =
+        profile.current_embedding(app_state.embedding.model(), app_state.embedding.dimension());
+
+    let computed;
+    let profile_emb: &[f32] = match stored {
+        Some(emb) => emb,
+        None => {
+            let mut conn = app_state.redis_pool.get().await.ok()?;
+            let profile_text = profile_text_for_embedding(&profile_meta, &rules);
+            computed = app_state
+                .embedding
+                .get_embedding(&profile_text, &mut conn)
+                .await
+                .ok()?;
+            &computed
+        }
+    };
+
+    let profile_norm = profile_emb.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+    let job_emb = job.embedding.as_ref()?;
+
+    let job_norm = job_emb.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+    let beckn_structure = job.beckn_structure.as_ref()?;
+
+    let mut string_sim_cache = std::collections::HashMap::new();
+
+    let (score, breakdown) = compute_empeding_match_score(
+        profile_emb,
+        profile_norm,
+        job_emb,
+        job_norm,
+        &profile_meta,
+        beckn_structure,
+        &app_state.config,
+        &rules,
+        &mut string_sim_cache,
+    );
+
+    let score_i16 = (score * 10.0).round() as i16;
+
+    Some((score_i16, serde_json::to_value(breakdown).ok()))
 }
 
 pub async fn compute_match_score_from_input(
@@ -74,15 +149,15 @@ pub async fn compute_match_score_from_input(
     job: &JobRow,
     profile_json: &JsonValue,
 ) -> (i16, Option<JsonValue>) {
-    let embedding_service = GcpEmbeddingService;
-
     let result: Option<(i16, Option<JsonValue>)> = async {
         let mut conn = app_state.redis_pool.get().await.ok()?;
+        let rules = app_state.match_rules.current();
 
-        let profile_text = profile_text_for_embedding(profile_json, &app_state.config);
+        let profile_text = profile_text_for_embedding(profile_json, &rules);
 
-        let profile_emb = embedding_service
-            .get_embedding(&profile_text, &mut conn, app_state)
+        let profile_emb = app_state
+            .embedding
+            .get_embedding(&profile_text, &mut conn)
             .await
             .ok()?;
 
@@ -95,7 +170,7 @@ pub async fn compute_match_score_from_input(
 
         let mut string_sim_cache = std::collections::HashMap::new();
 
-        let score = compute_empeding_match_score(
+        let (score, breakdown) = compute_empeding_match_score(
             &profile_emb,
             profile_norm,
             job_emb,
@@ -103,12 +178,13 @@ pub async fn compute_match_score_from_input(
             profile_json,
             beckn_structure,
             &app_state.config,
+            &rules,
             &mut string_sim_cache,
         );
 
         let score_i16 = (score * 10.0).round() as i16;
 
-        Some((score_i16, Some(json!({ "cosine_score": score }))))
+        Some((score_i16, serde_json::to_value(breakdown).ok()))
     }
     .await;
 
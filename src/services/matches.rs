@@ -0,0 +1,54 @@
+use crate::db::match_score::fetch_match_explain;
+use crate::state::AppState;
+use axum::{http::StatusCode, Json};
+use serde_json::{json, Value as JsonValue};
+use tracing::error;
+
+fn internal_error(message: String) -> (StatusCode, Json<JsonValue>) {
+    error!("{}", message);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({
+            "status": "error",
+            "message": message
+        })),
+    )
+}
+
+/// Returns the stored score breakdown for a profile/job pair.
+/// Read-only: missing, stale or pre-breakdown matches are reported with
+/// `stale: true` and left for the match-score cron to recompute.
+pub async fn explain_match(
+    app_state: &AppState,
+    profile_id: &str,
+    job_id: &str,
+) -> Result<Json<JsonValue>, (StatusCode, Json<JsonValue>)> {
+    let row = match fetch_match_explain(&app_state.db_pool, profile_id, job_id).await {
+        Ok(Some(row)) => row,
+        Ok(None) => {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(json!({
+                    "status": "error",
+                    "message": format!("No profile {} or job {}", profile_id, job_id)
+                })),
+            ))
+        }
+        Err(e) => return Err(internal_error(format!("DB error: {}", e))),
+    };
+
+    let has_rules = row
+        .score_breakdown
+        .as_ref()
+        .is_some_and(|b| b.get("rules").is_some());
+
+    Ok(Json(json!({
+        "status": "ok",
+        "profile_id": profile_id,
+        "job_id": job_id,
+        "match_score": row.match_score,
+        "computed_at": row.computed_at,
+        "stale": row.stale || !has_rules,
+        "score_breakdown": row.score_breakdown
+    })))
+}
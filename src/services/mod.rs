@@ -1,12 +1,19 @@
 pub mod admin;
+pub mod application_status;
+pub mod apply_saga;
+pub mod cancel;
 pub mod empeding;
 pub mod events;
 pub mod job_apply;
 pub mod job_draft;
+pub mod local_embedding;
 pub mod match_score;
+pub mod matches;
 pub mod payload_generator;
 pub mod profiles;
 pub mod search;
+pub mod search_stream;
 pub mod select;
+pub mod similar_jobs;
 pub mod status;
 pub mod webhook;
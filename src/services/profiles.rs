@@ -3,8 +3,9 @@ use crate::cron::fetch_profiles::{
 };
 use crate::cron::job_profile_match;
 use crate::db::profiles::fetch_beckn_profile_items;
-use crate::db::profiles::{store_profiles, NewProfile};
+use crate::db::profiles::{fetch_profile_by_profile_id, store_profiles, NewProfile};
 use crate::models::core::Context;
+use crate::services::match_score::ensure_profile_embeddings;
 use crate::services::payload_generator::build_profile_beckn_response;
 use crate::state::AppState;
 use crate::utils::http_client::get_json;
@@ -82,6 +83,11 @@ pub async fn sync_profile_by_id(state: &Arc<AppState>, profile_id: &str) -> anyh
 
     store_profiles(&state.db_pool, &[new_profile]).await?;
 
+    // Only this profile; the cron and reembed worker handle the backlog.
+    if let Some(stored) = fetch_profile_by_profile_id(&state.db_pool, profile_id).await? {
+        ensure_profile_embeddings(state, &mut [stored]).await;
+    }
+
     info!("✅ Profile {} synced successfully", profile_id);
 
     tokio::spawn({
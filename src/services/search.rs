@@ -1,12 +1,12 @@
 use crate::cron::job_profile_match;
-use crate::db::job::{fetch_jobs_by_ids, JobRow};
+use crate::db::job::{fetch_active_jobs_by_ids, fetch_filtered_job_ids, JobRow, JobSearchFilter};
 use crate::db::{
     job::{deactivate_stale_jobs, store_jobs},
     match_score::fetch_jobs_with_matches,
 };
 use crate::models::webhook::{Ack, AckResponse, AckStatus, WebhookPayload};
-use crate::services::empeding::{EmbeddingService, GcpEmbeddingService};
 use crate::services::match_score::compute_match_score_from_input;
+use crate::services::search_stream::search_channel;
 use crate::utils::job::update_embeddings_for_bpp;
 use crate::utils::shared::ack;
 use crate::vector::index_store::save_faiss;
@@ -19,9 +19,9 @@ use crate::{
             compute_empeding_match_score, job_text_for_embedding, profile_text_for_embedding,
         },
         hash::generate_query_hash,
-        http_client::post_json,
+        http_client::post_beckn_json,
         search::{
-            build_profile_json, extract_jobs_from_on_search, matches_exclude,
+            build_profile_json, claim_search_slot, extract_jobs_from_on_search, matches_exclude,
             matches_query_dynamic, send_open_jobs_search,
         },
     },
@@ -108,37 +108,7 @@ pub async fn handle_search(
     let adapter_url = format!("{}/search", config.bap.caller_uri);
 
     // --- Throttle BAP calls ---
-    let should_call_bap = match app_state.redis_pool.get().await {
-        Ok(mut conn) => {
-            let last_call_key = format!("last_call:{}", query_hash);
-            match conn.exists::<_, bool>(&last_call_key).await {
-                Ok(exists) if exists => {
-                    let secs = app_state.config.cache.throttle_secs;
-                    info!(
-                        ": Skipping BAP call (already called within last {} {})",
-                        if secs % 60 == 0 { secs / 60 } else { secs },
-                        if secs % 60 == 0 { "min" } else { "secs" }
-                    );
-                    false
-                }
-                _ => {
-                    let _: () = conn
-                        .set_ex::<_, _, ()>(
-                            &last_call_key,
-                            "1",
-                            app_state.config.cache.throttle_secs,
-                        )
-                        .await
-                        .unwrap_or_default();
-                    true
-                }
-            }
-        }
-        Err(e) => {
-            error!("Failed to get Redis connection for throttle check: {:?}", e);
-            true
-        }
-    };
+    let should_call_bap = claim_search_slot(&app_state, &query_hash).await;
 
     if should_call_bap {
         info!(
@@ -146,8 +116,9 @@ pub async fn handle_search(
             adapter_url
         );
         let payload_clone = payload.clone();
+        let app_state = app_state.clone();
         tokio::spawn(async move {
-            if let Err(e) = post_json(&adapter_url, payload_clone, None).await {
+            if let Err(e) = post_beckn_json(&app_state, &adapter_url, payload_clone).await {
                 error!("❌ Failed to send search to BAP adapter: {}", e);
             }
         });
@@ -194,7 +165,7 @@ pub async fn handle_on_search(
                                 if let Err(e) = conn
                                     .set_ex::<_, _, ()>(
                                         &redis_key,
-                                        data,
+                                        &data,
                                         app_state.config.cache.result_ttl_secs,
                                     )
                                     .await
@@ -203,6 +174,14 @@ pub async fn handle_on_search(
                                 } else {
                                     info!("✅ Stored response at key: {}", redis_key);
                                 }
+
+                                // Forward to any open /search/stream for this query
+                                if let Err(e) = conn
+                                    .publish::<_, _, ()>(search_channel(&query_hash), &data)
+                                    .await
+                                {
+                                    error!("❌ Failed to publish on_search: {:?}", e);
+                                }
                             }
                             Err(e) => {
                                 info!("❌ Failed to serialize payload: {:?}", e);
@@ -249,9 +228,6 @@ pub async fn handle_cron_on_search(
         }
     };
 
-    // Create embedding service instance
-    let embedding_service = GcpEmbeddingService;
-
     if let Some(bpp_id) = &payload.context.bpp_id {
         let redis_key = format!("cron_jobs:{}:{}", txn_id, bpp_id);
 
@@ -301,7 +277,8 @@ pub async fn handle_cron_on_search(
                                 .unwrap_or("Unknown Job")
                                 .to_string();
 
-                            let text = job_text_for_embedding(job, &app_state.config);
+                            let text =
+                                job_text_for_embedding(job, &app_state.match_rules.current());
 
                             if text.trim().is_empty() {
                                 info!(
@@ -321,10 +298,7 @@ pub async fn handle_cron_on_search(
                                 text.len()
                             );
 
-                            match embedding_service
-                                .get_embedding(&text, &mut conn, app_state)
-                                .await
-                            {
+                            match app_state.embedding.get_embedding(&text, &mut conn).await {
                                 Ok(embedding) => {
                                     let embedding_len = embedding.len();
 
@@ -527,7 +501,7 @@ pub async fn handle_cron_on_search(
             );
 
             let adapter_url = format!("{}/search", app_state.config.bap.caller_uri);
-            if let Err(e) = post_json(&adapter_url, next_payload, None).await {
+            if let Err(e) = post_beckn_json(app_state, &adapter_url, next_payload).await {
                 error!(
                     target: "cron",
                     "❌ Failed to request next_page = {} (txn_id={}): {}",
@@ -625,11 +599,12 @@ pub async fn handle_search_v2(
 
     // ✅ Compute embedding for profile
     let profile_embedding: Option<Vec<f32>> = if let Some(profile) = &req.profile {
-        let profile_text = profile_text_for_embedding(profile, &app_state.config);
+        let profile_text = profile_text_for_embedding(profile, &app_state.match_rules.current());
         info!("Profile text for embedding: {}", profile_text);
 
-        match GcpEmbeddingService
-            .get_embedding(&profile_text, &mut conn, &app_state)
+        match app_state
+            .embedding
+            .get_embedding(&profile_text, &mut conn)
             .await
         {
             Ok(vec) => Some(vec),
@@ -721,7 +696,7 @@ pub async fn handle_search_v2(
                                                 })
                                                 .unwrap_or(0.0);
 
-                                            let score = compute_empeding_match_score(
+                                            let (score, _) = compute_empeding_match_score(
                                                 profile_emb,
                                                 profile_norm,
                                                 &job_emb,
@@ -729,6 +704,7 @@ pub async fn handle_search_v2(
                                                 profile_meta,
                                                 &item,
                                                 &app_state.config,
+                                                &app_state.match_rules.current(),
                                                 &mut string_sim_cache,
                                             );
 
@@ -990,7 +966,7 @@ pub async fn handle_search_v3(
         .map(|s| s.trim())
         .filter(|s| !s.is_empty());
 
-    let data = fetch_jobs_with_matches(
+    let mut data = fetch_jobs_with_matches(
         &app_state.db_pool,
         profile_id,
         query,
@@ -1011,6 +987,14 @@ pub async fn handle_search_v3(
         )
     })?;
 
+    if !req.explain.unwrap_or(false) {
+        if let Some(items) = data.get_mut("items").and_then(|i| i.as_array_mut()) {
+            for item in items.iter_mut().filter_map(|i| i.as_object_mut()) {
+                item.remove("score_breakdown");
+            }
+        }
+    }
+
     Ok(Json(json!({
         "status": "ok",
         "page": page,
@@ -1023,8 +1007,6 @@ pub async fn handle_top_results(
     State(app_state): State<Arc<AppState>>,
     Json(req): Json<SearchTopKRequest>,
 ) -> Result<Json<JsonValue>, (StatusCode, Json<JsonValue>)> {
-    let embedding_service = GcpEmbeddingService;
-
     let mut redis_conn = match app_state.redis_pool.get().await {
         Ok(c) => c,
         Err(e) => {
@@ -1040,10 +1022,11 @@ pub async fn handle_top_results(
 
     let profile_json = build_profile_json(&req);
 
-    let profile_text = profile_text_for_embedding(&profile_json, &app_state.config);
+    let profile_text = profile_text_for_embedding(&profile_json, &app_state.match_rules.current());
 
-    let profile_embedding = match embedding_service
-        .get_embedding(&profile_text, &mut redis_conn, &app_state)
+    let profile_embedding = match app_state
+        .embedding
+        .get_embedding(&profile_text, &mut redis_conn)
         .await
     {
         Ok(v) if !v.is_empty() => v,
@@ -1059,50 +1042,101 @@ pub async fn handle_top_results(
     };
 
     let k = req.limit.unwrap_or(10) as usize;
-    let faiss = app_state.faiss.read().await;
 
-    let top_k = match faiss.search(profile_embedding.clone(), k).await {
-        Ok(res) => res,
-        Err(e) => {
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "status": "error",
-                    "message": format!("FAISS search failed: {}", e)
-                })),
-            ))
+    let filter = JobSearchFilter {
+        bpp_id: req.bpp_id.clone().filter(|v| !v.trim().is_empty()),
+        exclude_provider_id: None,
+        role: req.role.clone().filter(|v| !v.trim().is_empty()),
+        industry: req.industry.clone().filter(|v| !v.trim().is_empty()),
+        location: req.location.clone().filter(|v| !v.trim().is_empty()),
+    };
+
+    // Unfiltered requests skip the id scan and search the whole index.
+    let allowed: Option<HashSet<Uuid>> = if filter.is_empty() {
+        None
+    } else {
+        match fetch_filtered_job_ids(&app_state.db_pool, &filter).await {
+            Ok(ids) => Some(ids.into_iter().collect()),
+            Err(e) => {
+                return Err((
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({
+                        "status": "error",
+                        "message": format!("DB error: {}", e)
+                    })),
+                ))
+            }
         }
     };
 
-    let job_ids: Vec<uuid::Uuid> = top_k.iter().map(|(id, _)| *id).collect();
+    let faiss = app_state.faiss.read().await;
+    let ntotal = faiss.ntotal().await as usize;
+
+    // The index keeps deactivated jobs until the next rebuild, so the
+    // unfiltered search over-fetches and doubles until k active jobs load.
+    let mut fetch = (k * app_state.config.vector.overfetch.max(1)).min(ntotal.max(1));
+
+    let (top_k, jobs) = loop {
+        let search = match &allowed {
+            Some(allowed) => {
+                faiss
+                    .search_filtered(profile_embedding.clone(), k, allowed)
+                    .await
+            }
+            None => faiss.search(profile_embedding.clone(), fetch).await,
+        };
 
-    let jobs = match fetch_jobs_by_ids(&app_state.db_pool, &job_ids).await {
-        Ok(j) => j,
-        Err(e) => {
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "status": "error",
-                    "message": format!("DB error: {}", e)
-                })),
-            ))
+        let hits = match search {
+            Ok(res) => res,
+            Err(e) => {
+                return Err((
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({
+                        "status": "error",
+                        "message": format!("FAISS search failed: {}", e)
+                    })),
+                ))
+            }
+        };
+
+        let job_ids: Vec<uuid::Uuid> = hits.iter().map(|(id, _)| *id).collect();
+
+        let jobs = match fetch_active_jobs_by_ids(&app_state.db_pool, &job_ids).await {
+            Ok(j) => j,
+            Err(e) => {
+                return Err((
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({
+                        "status": "error",
+                        "message": format!("DB error: {}", e)
+                    })),
+                ))
+            }
+        };
+
+        if allowed.is_some() || jobs.len() >= k || fetch >= ntotal {
+            break (hits, jobs);
         }
+        fetch = (fetch * 2).min(ntotal);
     };
 
     let job_map: HashMap<uuid::Uuid, JobRow> = jobs.into_iter().map(|j| (j.id, j)).collect();
 
     let mut results = Vec::new();
 
-    for (job_id, _) in top_k {
-        if let Some(job) = job_map.get(&job_id) {
-            let (score, _) = compute_match_score_from_input(&app_state, job, &profile_json).await;
+    let active_hits = top_k
+        .iter()
+        .filter_map(|(job_id, _)| job_map.get(job_id))
+        .take(k);
 
-            results.push(json!({
-                "job_id": job.job_id,
-                "match_score": score,
-                "metadata": job.beckn_structure
-            }));
-        }
+    for job in active_hits {
+        let (score, _) = compute_match_score_from_input(&app_state, job, &profile_json).await;
+
+        results.push(json!({
+            "job_id": job.job_id,
+            "match_score": score,
+            "metadata": job.beckn_structure
+        }));
     }
 
     results.sort_by(|a, b| {
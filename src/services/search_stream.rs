@@ -0,0 +1,241 @@
+use crate::models::search::{
+    Intent, Item, ItemDescriptor, Pagination, Provider, ProviderDescriptor, SearchMessage,
+    SearchStreamQuery,
+};
+use crate::services::payload_generator::build_beckn_payload;
+use crate::state::AppState;
+use crate::utils::beckn_context::parse_iso8601_duration;
+use crate::utils::hash::generate_query_hash;
+use crate::utils::http_client::post_beckn_json;
+use crate::utils::search::claim_search_slot;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    Json,
+};
+use futures::stream::{self, Stream, StreamExt};
+use redis::AsyncCommands;
+use serde_json::{json, Value as JsonValue};
+use std::collections::BTreeSet;
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::time::{sleep_until, Duration, Instant};
+use tracing::{error, info};
+use uuid::Uuid;
+
+pub const SEARCH_CHANNEL_PREFIX: &str = "beckn:search:";
+
+const DEFAULT_STREAM_TTL: Duration = Duration::from_secs(30);
+
+pub fn search_channel(query_hash: &str) -> String {
+    format!("{}{}", SEARCH_CHANNEL_PREFIX, query_hash)
+}
+
+fn build_search_message(query: &SearchStreamQuery) -> SearchMessage {
+    let pagination = (query.page.is_some() || query.limit.is_some()).then_some(Pagination {
+        page: query.page,
+        limit: query.limit,
+    });
+
+    SearchMessage {
+        intent: Intent {
+            item: query.query.as_ref().map(|name| Item {
+                descriptor: Some(ItemDescriptor { name: name.clone() }),
+                tags: None,
+            }),
+            provider: query.provider.as_ref().map(|name| Provider {
+                descriptor: Some(ProviderDescriptor {
+                    id: None,
+                    name: name.clone(),
+                }),
+                locations: None,
+            }),
+            fulfillment: None,
+        },
+        pagination,
+        options: None,
+    }
+}
+
+fn catalog_event(payload: &JsonValue, cached: bool) -> Event {
+    let bpp_id = payload["context"]["bpp_id"].as_str().unwrap_or_default();
+    Event::default().event("catalog").data(
+        json!({
+            "bpp_id": bpp_id,
+            "cached": cached,
+            "payload": payload
+        })
+        .to_string(),
+    )
+}
+
+async fn cached_results(app_state: &AppState, query_hash: &str) -> Vec<JsonValue> {
+    let mut conn = match app_state.redis_pool.get().await {
+        Ok(c) => c,
+        Err(e) => {
+            error!("Failed to get Redis connection from pool: {:?}", e);
+            return vec![];
+        }
+    };
+
+    let pattern = format!("search:{}:*", query_hash);
+    let keys: Vec<String> = match conn.scan_match::<_, String>(&pattern).await {
+        Ok(iter) => iter.collect().await,
+        Err(e) => {
+            error!("Failed to scan Redis for {}: {:?}", pattern, e);
+            return vec![];
+        }
+    };
+
+    let mut results = vec![];
+    for key in keys {
+        if let Ok(value) = conn.get::<_, String>(&key).await {
+            match serde_json::from_str::<JsonValue>(&value) {
+                Ok(json_value) => results.push(json_value),
+                Err(_) => error!("Failed to parse cached value for key: {}", key),
+            }
+        }
+    }
+    results
+}
+
+struct LiveState {
+    messages: Pin<Box<dyn Stream<Item = redis::Msg> + Send>>,
+    deadline: Instant,
+    txn_id: String,
+    responded: BTreeSet<String>,
+    cached: Vec<String>,
+    done: bool,
+}
+
+/// Yields each on_search for the query as it is published, then a summary once the TTL is up.
+fn live_events(state: LiveState) -> impl Stream<Item = Result<Event, Infallible>> {
+    stream::unfold(state, |mut state| async move {
+        if state.done {
+            return None;
+        }
+
+        loop {
+            tokio::select! {
+                _ = sleep_until(state.deadline) => break,
+                msg = state.messages.next() => {
+                    let Some(msg) = msg else { break };
+                    let Ok(data) = msg.get_payload::<String>() else { continue };
+                    let Ok(payload) = serde_json::from_str::<JsonValue>(&data) else { continue };
+
+                    if let Some(bpp_id) = payload["context"]["bpp_id"].as_str() {
+                        state.responded.insert(bpp_id.to_string());
+                    }
+                    return Some((Ok(catalog_event(&payload, false)), state));
+                }
+            }
+        }
+
+        state.done = true;
+        let summary = Event::default().event("summary").data(
+            json!({
+                "transaction_id": state.txn_id,
+                "responded_bpps": state.responded,
+                "cached_bpps": state.cached,
+            })
+            .to_string(),
+        );
+        Some((Ok(summary), state))
+    })
+}
+
+pub async fn handle_search_stream(
+    State(app_state): State<Arc<AppState>>,
+    Query(query): Query<SearchStreamQuery>,
+) -> Response {
+    let message = build_search_message(&query);
+    let query_hash = generate_query_hash(&message);
+    let txn_id = format!("txn-{}", Uuid::new_v4());
+    let message_id = format!("msg-{}", Uuid::new_v4());
+
+    // Subscribe before the search goes out so no on_search can slip past.
+    let mut pubsub = match app_state.redis_client.get_async_pubsub().await {
+        Ok(p) => p,
+        Err(e) => {
+            error!("❌ Failed to open Redis pub/sub for search stream: {:?}", e);
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(json!({ "error": "Search streaming is unavailable" })),
+            )
+                .into_response();
+        }
+    };
+    if let Err(e) = pubsub.subscribe(search_channel(&query_hash)).await {
+        error!("❌ Failed to subscribe to search channel: {:?}", e);
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "error": "Search streaming is unavailable" })),
+        )
+            .into_response();
+    }
+
+    if let Ok(mut conn) = app_state.redis_pool.get().await {
+        let txn_key = format!("txn_to_query:{}", txn_id);
+        let _: () = conn
+            .set_ex::<_, _, ()>(&txn_key, &query_hash, app_state.config.cache.txn_ttl_secs)
+            .await
+            .unwrap_or_else(|e| error!("Failed to cache txn_id: {:?}", e));
+    }
+
+    let cached = cached_results(&app_state, &query_hash).await;
+    let cached_bpps = cached
+        .iter()
+        .filter_map(|c| c["context"]["bpp_id"].as_str().map(|s| s.to_string()))
+        .collect();
+
+    // A throttled query still streams on_search from the search that is already in flight.
+    if claim_search_slot(&app_state, &query_hash).await {
+        let payload = build_beckn_payload(
+            &app_state.config,
+            &txn_id,
+            &message_id,
+            &message,
+            "search",
+            None,
+            None,
+        );
+        let adapter_url = format!("{}/search", app_state.config.bap.caller_uri);
+        if let Err(e) = post_beckn_json(&app_state, &adapter_url, payload).await {
+            error!("❌ Failed to send search to BAP adapter: {}", e);
+        }
+    }
+
+    let ttl = parse_iso8601_duration(&app_state.config.bap.ttl)
+        .and_then(|d| d.to_std().ok())
+        .unwrap_or(DEFAULT_STREAM_TTL);
+
+    info!(
+        "📡 Streaming search results (txn_id={}, query_hash={}, ttl={:?})",
+        txn_id, query_hash, ttl
+    );
+
+    let cached_events = stream::iter(
+        cached
+            .iter()
+            .map(|c| Ok(catalog_event(c, true)))
+            .collect::<Vec<_>>(),
+    );
+
+    let live = live_events(LiveState {
+        messages: Box::pin(pubsub.into_on_message()),
+        deadline: Instant::now() + ttl,
+        txn_id,
+        responded: BTreeSet::new(),
+        cached: cached_bpps,
+        done: false,
+    });
+
+    Sse::new(cached_events.chain(live))
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
@@ -1,5 +1,8 @@
 use crate::services::payload_generator::build_beckn_payload;
-use crate::{models::select::SelectRequest, state::AppState, utils::http_client::post_json};
+use crate::utils::correlation::{
+    cancel_waiter, correlation_key, publish_response, register_waiter, wait_for_response, WaitError,
+};
+use crate::{models::select::SelectRequest, state::AppState, utils::http_client::post_beckn_json};
 use axum::{
     extract::State,
     http::StatusCode,
@@ -7,8 +10,7 @@ use axum::{
     Json,
 };
 use serde::Serialize;
-use tokio::sync::oneshot::channel;
-use tokio::time::{timeout, Duration};
+use tokio::time::Duration;
 use uuid::Uuid;
 #[derive(Serialize)]
 struct ErrorResponse {
@@ -16,7 +18,6 @@ struct ErrorResponse {
 }
 use crate::models::webhook::{Ack, AckResponse, AckStatus, WebhookPayload};
 use std::sync::Arc;
-use tracing::info;
 pub async fn handle_select(
     State(app_state): State<Arc<AppState>>,
     Json(req): Json<SelectRequest>,
@@ -25,12 +26,8 @@ pub async fn handle_select(
 
     let transaction_id = ctx.transaction_id.clone();
     let message_id = format!("msg-{}", Uuid::new_v4());
-    let (tx, rx) = channel();
-    let unique_key = format!("{}:{}", transaction_id, message_id);
-    app_state
-        .shared_state
-        .pending_searches
-        .insert(unique_key, tx);
+    let unique_key = correlation_key(&transaction_id, &message_id);
+    let rx = register_waiter(&app_state, &unique_key);
 
     let config = app_state.config.clone();
     let adapter_url = format!("{}/select", config.bap.caller_uri);
@@ -45,11 +42,8 @@ pub async fn handle_select(
         Some(&req.context.bpp_uri),
     );
 
-    if let Err(e) = post_json(&adapter_url, payload, None).await {
-        app_state
-            .shared_state
-            .pending_searches
-            .remove(&transaction_id);
+    if let Err(e) = post_beckn_json(&app_state, &adapter_url, payload).await {
+        cancel_waiter(&app_state, &unique_key);
         return Err((
             StatusCode::BAD_GATEWAY,
             Json(ErrorResponse {
@@ -59,28 +53,22 @@ pub async fn handle_select(
             .into_response());
     }
 
-    match timeout(Duration::from_secs(10), rx).await {
-        Ok(Ok(on_select_payload)) => Ok(Json(on_select_payload)),
-        Ok(Err(_recv_err)) => Err((
+    match wait_for_response(&app_state, &unique_key, rx, Duration::from_secs(10)).await {
+        Ok(on_select_payload) => Ok(Json(on_select_payload)),
+        Err(WaitError::ChannelClosed) => Err((
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse {
                 error: "Internal channel error receiving on_select".to_string(),
             }),
         )
             .into_response()),
-        Err(_elapsed) => {
-            app_state
-                .shared_state
-                .pending_searches
-                .remove(&transaction_id);
-            Err((
-                StatusCode::GATEWAY_TIMEOUT,
-                Json(ErrorResponse {
-                    error: "Timeout waiting for on_select response".to_string(),
-                }),
-            )
-                .into_response())
-        }
+        Err(WaitError::Timeout) => Err((
+            StatusCode::GATEWAY_TIMEOUT,
+            Json(ErrorResponse {
+                error: "Timeout waiting for on_select response".to_string(),
+            }),
+        )
+            .into_response()),
     }
 }
 
@@ -90,27 +78,7 @@ pub async fn handle_on_select(
     txn_id: &str,
     msg_id: &str,
 ) -> impl IntoResponse {
-    let unique_key = format!("{}:{}", txn_id, msg_id);
-    match app_state.shared_state.pending_searches.remove(&unique_key) {
-        Some((_, sender)) => match serde_json::to_value(payload) {
-            Ok(json_value) => {
-                if let Err(e) = sender.send(json_value) {
-                    info!("⚠️ Failed to send on_select payload: {:?}", e);
-                } else {
-                    info!("✅ Delivered on_select to waiting request");
-                }
-            }
-            Err(e) => {
-                info!("❌ Failed to serialize payload: {:?}", e);
-            }
-        },
-        None => {
-            info!(
-                "⚠️ No pending /select request found for transaction_id = {}",
-                txn_id
-            );
-        }
-    }
+    publish_response(app_state, "on_select", payload, txn_id, msg_id).await;
 
     Json(AckResponse {
         message: AckStatus {
@@ -0,0 +1,160 @@
+use crate::db::job::{fetch_similar_jobs_by_ids, fetch_similarity_source, SimilarJobRow};
+use crate::models::search::SimilarJobsQuery;
+use crate::state::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde_json::{json, Value as JsonValue};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tracing::error;
+use uuid::Uuid;
+
+const DEFAULT_LIMIT: usize = 10;
+const MAX_LIMIT: usize = 50;
+
+fn internal_error(message: String) -> (StatusCode, Json<JsonValue>) {
+    error!("{}", message);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({
+            "status": "error",
+            "message": message
+        })),
+    )
+}
+
+pub async fn handle_similar_jobs(
+    Path(job_id): Path<String>,
+    Query(params): Query<SimilarJobsQuery>,
+    State(app_state): State<Arc<AppState>>,
+) -> Result<Json<JsonValue>, (StatusCode, Json<JsonValue>)> {
+    let limit = params
+        .limit
+        .map(|l| l as usize)
+        .unwrap_or(DEFAULT_LIMIT)
+        .clamp(1, MAX_LIMIT);
+
+    let source = match fetch_similarity_source(&app_state.db_pool, &job_id).await {
+        Ok(Some(job)) => job,
+        Ok(None) => {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(json!({
+                    "status": "error",
+                    "message": format!("Job {} not found", job_id)
+                })),
+            ))
+        }
+        Err(e) => return Err(internal_error(format!("DB error: {}", e))),
+    };
+
+    if source.embedding.is_none() {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({
+                "status": "error",
+                "message": format!("Job {} has no embedding yet", job_id)
+            })),
+        ));
+    }
+
+    let model = app_state.embedding.model();
+    let dimension = app_state.embedding.dimension();
+    let Some(embedding) = source.current_embedding(model, dimension) else {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({
+                "status": "error",
+                "message": format!(
+                    "Job {} has not been re-embedded with {} ({} dims) yet",
+                    job_id, model, dimension
+                )
+            })),
+        ));
+    };
+
+    // Over-fetch so there is room to drop the source provider's jobs and
+    // still spread results across the rest.
+    let pool_size = limit * app_state.config.vector.overfetch.max(1);
+    let faiss = app_state.faiss.read().await.clone();
+    let hits = match faiss.search(embedding.to_vec(), pool_size).await {
+        Ok(hits) => hits,
+        Err(e) => return Err(internal_error(format!("FAISS search failed: {}", e))),
+    };
+
+    let ids: Vec<Uuid> = hits.iter().map(|(id, _)| *id).collect();
+    let rows = match fetch_similar_jobs_by_ids(&app_state.db_pool, &ids).await {
+        Ok(rows) => rows,
+        Err(e) => return Err(internal_error(format!("DB error: {}", e))),
+    };
+
+    // Other providers only; the source job is dropped along with its provider.
+    let mut by_id: HashMap<Uuid, SimilarJobRow> = rows
+        .into_iter()
+        .filter(|r| r.provider_id != source.provider_id)
+        .map(|r| (r.id, r))
+        .collect();
+    let ranked: Vec<(SimilarJobRow, f32)> = hits
+        .into_iter()
+        .filter_map(|(id, score)| Some((by_id.remove(&id)?, score)))
+        .collect();
+
+    let data: Vec<JsonValue> = diversify_by_provider(ranked, limit)
+        .into_iter()
+        .map(|(job, score)| {
+            json!({
+                "job_id": job.job_id,
+                "provider_id": job.provider_id,
+                "bpp_id": job.bpp_id,
+                "similarity": score,
+                "metadata": job.beckn_structure
+            })
+        })
+        .collect();
+
+    Ok(Json(json!({
+        "status": "ok",
+        "job_id": source.job_id,
+        "data": data
+    })))
+}
+
+/// Round-robins over providers in order of their best hit, so one provider
+/// can't fill the list while keeping each provider's own ranking intact.
+fn diversify_by_provider(
+    ranked: Vec<(SimilarJobRow, f32)>,
+    limit: usize,
+) -> Vec<(SimilarJobRow, f32)> {
+    let mut groups: Vec<VecDeque<(SimilarJobRow, f32)>> = Vec::new();
+    let mut group_of: HashMap<String, usize> = HashMap::new();
+
+    for (job, score) in ranked {
+        let idx = *group_of.entry(job.provider_id.clone()).or_insert_with(|| {
+            groups.push(VecDeque::new());
+            groups.len() - 1
+        });
+        groups[idx].push_back((job, score));
+    }
+
+    let mut output = Vec::with_capacity(limit);
+    while output.len() < limit {
+        let mut took_any = false;
+        for group in groups.iter_mut() {
+            if output.len() == limit {
+                break;
+            }
+            if let Some(hit) = group.pop_front() {
+                output.push(hit);
+                took_any = true;
+            }
+        }
+        if !took_any {
+            break;
+        }
+    }
+
+    output
+}
@@ -1,5 +1,9 @@
+use crate::services::application_status::record_status_update;
 use crate::services::payload_generator::build_beckn_payload;
-use crate::{models::status::StatusRequest, state::AppState, utils::http_client::post_json};
+use crate::utils::correlation::{
+    cancel_waiter, correlation_key, publish_response, register_waiter, wait_for_response, WaitError,
+};
+use crate::{models::status::StatusRequest, state::AppState, utils::http_client::post_beckn_json};
 use axum::{
     extract::State,
     http::StatusCode,
@@ -8,15 +12,13 @@ use axum::{
 };
 use serde::Serialize;
 use std::sync::Arc;
-use tokio::sync::oneshot::channel;
-use tokio::time::{timeout, Duration};
+use tokio::time::Duration;
 use uuid::Uuid;
 #[derive(Serialize)]
 struct ErrorResponse {
     error: String,
 }
 use crate::models::webhook::{Ack, AckResponse, AckStatus, WebhookPayload};
-use tracing::info;
 
 pub async fn handle_status(
     State(app_state): State<Arc<AppState>>,
@@ -26,12 +28,8 @@ pub async fn handle_status(
 
     let transaction_id = ctx.transaction_id.clone();
     let message_id = format!("msg-{}", Uuid::new_v4());
-    let (tx, rx) = channel();
-    let unique_key = format!("{}:{}", transaction_id, message_id);
-    app_state
-        .shared_state
-        .pending_searches
-        .insert(unique_key, tx);
+    let unique_key = correlation_key(&transaction_id, &message_id);
+    let rx = register_waiter(&app_state, &unique_key);
 
     let config = app_state.config.clone();
     let adapter_url = format!("{}/status", config.bap.caller_uri);
@@ -46,11 +44,8 @@ pub async fn handle_status(
         Some(&req.context.bpp_uri),
     );
 
-    if let Err(e) = post_json(&adapter_url, payload, None).await {
-        app_state
-            .shared_state
-            .pending_searches
-            .remove(&transaction_id);
+    if let Err(e) = post_beckn_json(&app_state, &adapter_url, payload).await {
+        cancel_waiter(&app_state, &unique_key);
         return Err((
             StatusCode::BAD_GATEWAY,
             Json(ErrorResponse {
@@ -60,28 +55,22 @@ pub async fn handle_status(
             .into_response());
     }
 
-    match timeout(Duration::from_secs(10), rx).await {
-        Ok(Ok(on_select_payload)) => Ok(Json(on_select_payload)),
-        Ok(Err(_recv_err)) => Err((
+    match wait_for_response(&app_state, &unique_key, rx, Duration::from_secs(10)).await {
+        Ok(on_status_payload) => Ok(Json(on_status_payload)),
+        Err(WaitError::ChannelClosed) => Err((
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse {
                 error: "Internal channel error receiving on_status".to_string(),
             }),
         )
             .into_response()),
-        Err(_elapsed) => {
-            app_state
-                .shared_state
-                .pending_searches
-                .remove(&transaction_id);
-            Err((
-                StatusCode::GATEWAY_TIMEOUT,
-                Json(ErrorResponse {
-                    error: "Timeout waiting for on_status response".to_string(),
-                }),
-            )
-                .into_response())
-        }
+        Err(WaitError::Timeout) => Err((
+            StatusCode::GATEWAY_TIMEOUT,
+            Json(ErrorResponse {
+                error: "Timeout waiting for on_status response".to_string(),
+            }),
+        )
+            .into_response()),
     }
 }
 
@@ -91,27 +80,18 @@ pub async fn handle_on_status(
     txn_id: &str,
     msg_id: &str,
 ) -> impl IntoResponse {
-    let unique_key = format!("{}:{}", txn_id, msg_id);
-    match app_state.shared_state.pending_searches.remove(&unique_key) {
-        Some((_, sender)) => match serde_json::to_value(payload) {
-            Ok(json_value) => {
-                if let Err(e) = sender.send(json_value) {
-                    info!("⚠️ Failed to send on_status payload: {:?}", e);
-                } else {
-                    info!("✅ Delivered on_status to waiting request");
-                }
-            }
-            Err(e) => {
-                info!("❌ Failed to serialize payload: {:?}", e);
-            }
+    record_status_update(app_state, "on_status", payload).await;
+    publish_response(app_state, "on_status", payload, txn_id, msg_id).await;
+
+    Json(AckResponse {
+        message: AckStatus {
+            ack: Ack { status: "ACK" },
         },
-        None => {
-            info!(
-                "⚠️ No pending /status request found for transaction_id = {}",
-                txn_id
-            );
-        }
-    }
+    })
+}
+
+pub async fn handle_on_update(app_state: &AppState, payload: &WebhookPayload) -> impl IntoResponse {
+    record_status_update(app_state, "on_update", payload).await;
 
     Json(AckResponse {
         message: AckStatus {
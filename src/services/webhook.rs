@@ -1,27 +1,75 @@
 use crate::models::core::Context;
-use crate::models::webhook::{Ack, AckResponse, AckStatus, WebhookPayload};
+use crate::models::webhook::{
+    Ack, AckResponse, AckStatus, BecknError, NackResponse, WebhookPayload,
+};
 use crate::services::{
+    cancel::handle_on_cancel,
     job_apply::{handle_on_confirm, handle_on_init},
     profiles::handle_search_profiles,
     search::handle_on_search,
     select::handle_on_select,
-    status::handle_on_status,
+    status::{handle_on_status, handle_on_update},
 };
 use crate::state::AppState;
+use crate::utils::beckn_context::{validate_context, ExpectedContext, INVALID_REQUEST};
 use crate::workers::processor::spawn_processing_task;
 use axum::{
-    extract::{Path, State},
-    response::IntoResponse,
+    extract::{rejection::JsonRejection, Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
     Json,
 };
 use serde_json::Value;
 use std::sync::Arc;
 use tracing::{debug, info};
+
+fn nack(error: BecknError) -> Response {
+    info!(
+        "⚠️ NACK {} at {}: {}",
+        error.code, error.path, error.message
+    );
+    (StatusCode::BAD_REQUEST, Json(NackResponse::new(error))).into_response()
+}
+
+/// Unwraps the body and validates its context; any error is sent back as a NACK.
+fn validated_payload(
+    payload: Result<Json<WebhookPayload>, JsonRejection>,
+    expected: &ExpectedContext,
+    action: &str,
+    app_state: &AppState,
+) -> Result<WebhookPayload, BecknError> {
+    let Json(payload) = payload.map_err(|rejection| BecknError {
+        code: INVALID_REQUEST.to_string(),
+        path: "$".to_string(),
+        message: rejection.body_text(),
+    })?;
+
+    validate_context(
+        &app_state.config.context_validation,
+        expected,
+        action,
+        &payload.context,
+        &payload.message,
+        payload.error.as_ref(),
+    )?;
+
+    Ok(payload)
+}
+
 pub async fn webhook_handler(
     Path(action): Path<String>,
     State(app_state): State<Arc<AppState>>,
-    Json(payload): Json<WebhookPayload>,
-) -> impl IntoResponse {
+    payload: Result<Json<WebhookPayload>, JsonRejection>,
+) -> Response {
+    let expected = ExpectedContext {
+        domain: &app_state.config.bap.domain,
+        version: &app_state.config.bap.version,
+    };
+    let payload = match validated_payload(payload, &expected, &action, &app_state) {
+        Ok(p) => p,
+        Err(error) => return nack(error),
+    };
+
     let txn_id = payload.context.transaction_id.clone();
     let msg_id = payload.context.message_id.clone();
     info!("webhook called: action = {}, txn_id = {}", action, txn_id);
@@ -43,6 +91,10 @@ pub async fn webhook_handler(
         "on_status" => handle_on_status(&app_state, &payload, &txn_id, &msg_id)
             .await
             .into_response(),
+        "on_update" => handle_on_update(&app_state, &payload).await.into_response(),
+        "on_cancel" => handle_on_cancel(&app_state, &payload, &txn_id, &msg_id)
+            .await
+            .into_response(),
         _ => Json(AckResponse {
             message: AckStatus {
                 ack: Ack { status: "ACK" },
@@ -57,8 +109,17 @@ pub async fn webhook_handler(
 pub async fn webhook_handler_profiles(
     Path(action): Path<String>,
     State(app_state): State<Arc<AppState>>,
-    Json(payload): Json<WebhookPayload>,
-) -> impl IntoResponse {
+    payload: Result<Json<WebhookPayload>, JsonRejection>,
+) -> Response {
+    let expected = ExpectedContext {
+        domain: &app_state.config.bpp.domain,
+        version: &app_state.config.bpp.version,
+    };
+    let payload = match validated_payload(payload, &expected, &action, &app_state) {
+        Ok(p) => p,
+        Err(error) => return nack(error),
+    };
+
     info!(
         target: "webhook",
         "🟢 [ Adapter → BPP] Request received | txn_id: {}, msg_id: {}, action: {}, timestamp: {}",
@@ -78,7 +139,7 @@ pub async fn webhook_handler_profiles(
                 ack: Ack { status: "ACK" },
             },
         };
-        return Json(ack);
+        return Json(ack).into_response();
     }
 
     spawn_processing_task(payload.context, payload.message, action, app_state);
@@ -89,7 +150,7 @@ pub async fn webhook_handler_profiles(
         },
     };
 
-    Json(ack)
+    Json(ack).into_response()
 }
 
 pub async fn generate_response(
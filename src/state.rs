@@ -5,6 +5,8 @@ use tokio::sync::oneshot;
 pub type OnSearchResponse = serde_json::Value;
 
 use crate::config::AppConfig;
+use crate::services::empeding::EmbeddingService;
+use crate::utils::match_rules::MatchRulesHandle;
 use crate::vector::faiss_service::FaissService;
 use deadpool_redis::Pool;
 use sqlx::PgPool;
@@ -15,8 +17,11 @@ pub struct AppState {
     pub config: Arc<AppConfig>,
     pub shared_state: SharedState,
     pub redis_pool: Pool,
+    pub redis_client: redis::Client,
     pub db_pool: PgPool,
     pub faiss: Arc<RwLock<FaissService>>,
+    pub embedding: Arc<dyn EmbeddingService>,
+    pub match_rules: Arc<MatchRulesHandle>,
 }
 
 #[derive(Clone, Default)]
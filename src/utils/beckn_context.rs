@@ -0,0 +1,203 @@
+use crate::config::ContextValidationConfig;
+use crate::models::core::Context;
+use crate::models::webhook::BecknError;
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+
+pub const INVALID_REQUEST: &str = "10000";
+pub const INVALID_CONTEXT: &str = "10001";
+pub const STALE_REQUEST: &str = "20000";
+
+/// Domain and version the receiving side of this participant speaks.
+pub struct ExpectedContext<'a> {
+    pub domain: &'a str,
+    pub version: &'a str,
+}
+
+fn context_error(code: &str, field: &str, message: impl Into<String>) -> BecknError {
+    BecknError {
+        code: code.to_string(),
+        path: format!("context.{}", field),
+        message: message.into(),
+    }
+}
+
+/// Parses the ISO 8601 durations used for `context.ttl` (e.g. `PT30S`, `P1DT2H`).
+pub fn parse_iso8601_duration(value: &str) -> Option<Duration> {
+    let rest = value.trim().strip_prefix('P')?;
+    let (date_part, time_part) = match rest.split_once('T') {
+        Some((d, t)) => (d, Some(t)),
+        None => (rest, None),
+    };
+
+    let mut total = Duration::zero();
+    let mut parsed_any = false;
+
+    let mut apply = |part: &str, units: &[(char, i64)]| -> Option<()> {
+        let mut number = String::new();
+        for ch in part.chars() {
+            if ch.is_ascii_digit() || ch == '.' {
+                number.push(ch);
+                continue;
+            }
+            let secs = units.iter().find(|(u, _)| *u == ch)?.1;
+            let amount: f64 = number.parse().ok()?;
+            total += Duration::milliseconds((amount * secs as f64 * 1000.0) as i64);
+            number.clear();
+            parsed_any = true;
+        }
+        number.is_empty().then_some(())
+    };
+
+    apply(date_part, &[('W', 604_800), ('D', 86_400)])?;
+    if let Some(time) = time_part {
+        if time.is_empty() {
+            return None;
+        }
+        apply(time, &[('H', 3_600), ('M', 60), ('S', 1)])?;
+    }
+
+    parsed_any.then_some(total)
+}
+
+fn require_message_field(message: &Value, field: &str) -> Result<(), BecknError> {
+    match message.get(field) {
+        Some(v) if !v.is_null() => Ok(()),
+        _ => Err(BecknError {
+            code: INVALID_REQUEST.to_string(),
+            path: format!("message.{}", field),
+            message: format!("message.{} is required", field),
+        }),
+    }
+}
+
+/// Checks a received context (and the message shape its action implies)
+/// before any handler touches it.
+pub fn validate_context(
+    config: &ContextValidationConfig,
+    expected: &ExpectedContext,
+    route_action: &str,
+    context: &Context,
+    message: &Value,
+    error: Option<&Value>,
+) -> Result<(), BecknError> {
+    if context.action != route_action {
+        return Err(context_error(
+            INVALID_CONTEXT,
+            "action",
+            format!(
+                "context.action '{}' does not match endpoint '{}'",
+                context.action, route_action
+            ),
+        ));
+    }
+
+    if context.domain != expected.domain {
+        return Err(context_error(
+            INVALID_CONTEXT,
+            "domain",
+            format!(
+                "Unsupported domain '{}', expected '{}'",
+                context.domain, expected.domain
+            ),
+        ));
+    }
+
+    if context.version != expected.version {
+        return Err(context_error(
+            INVALID_CONTEXT,
+            "version",
+            format!(
+                "Unsupported version '{}', expected '{}'",
+                context.version, expected.version
+            ),
+        ));
+    }
+
+    for (field, value) in [
+        ("transaction_id", &context.transaction_id),
+        ("message_id", &context.message_id),
+        ("bap_id", &context.bap_id),
+        ("bap_uri", &context.bap_uri),
+    ] {
+        if value.trim().is_empty() {
+            return Err(context_error(
+                INVALID_CONTEXT,
+                field,
+                format!("context.{} is required", field),
+            ));
+        }
+    }
+
+    // Callbacks come from a BPP, which has to identify itself.
+    if context.action.starts_with("on_") {
+        for (field, value) in [("bpp_id", &context.bpp_id), ("bpp_uri", &context.bpp_uri)] {
+            if value.as_deref().is_none_or(|v| v.trim().is_empty()) {
+                return Err(context_error(
+                    INVALID_CONTEXT,
+                    field,
+                    format!("context.{} is required for {}", field, context.action),
+                ));
+            }
+        }
+    }
+
+    let timestamp = DateTime::parse_from_rfc3339(&context.timestamp)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|_| {
+            context_error(
+                INVALID_CONTEXT,
+                "timestamp",
+                "context.timestamp must be an RFC 3339 date-time",
+            )
+        })?;
+
+    let now = Utc::now();
+    let max_skew = Duration::seconds(config.max_clock_skew_secs as i64);
+
+    if timestamp - now > max_skew {
+        return Err(context_error(
+            STALE_REQUEST,
+            "timestamp",
+            "context.timestamp is in the future",
+        ));
+    }
+
+    if config.enforce_ttl {
+        if let Some(ttl) = &context.ttl {
+            let ttl_duration = parse_iso8601_duration(ttl).ok_or_else(|| {
+                context_error(
+                    INVALID_CONTEXT,
+                    "ttl",
+                    format!("context.ttl '{}' is not an ISO 8601 duration", ttl),
+                )
+            })?;
+
+            if timestamp + ttl_duration + max_skew < now {
+                return Err(context_error(
+                    STALE_REQUEST,
+                    "ttl",
+                    format!(
+                        "Request expired: ttl {} elapsed since {}",
+                        ttl, context.timestamp
+                    ),
+                ));
+            }
+        }
+    }
+
+    // BPP failure callbacks carry an error object instead of the usual message,
+    // and the waiters still need to see them.
+    let failed = error.is_some_and(|e| e.is_object());
+
+    match context.action.as_str() {
+        "search" => require_message_field(message, "intent"),
+        "on_search" if !failed => require_message_field(message, "catalog"),
+        "on_select" | "on_init" | "on_confirm" | "on_status" | "on_update" | "on_cancel"
+            if !failed =>
+        {
+            require_message_field(message, "order")
+        }
+        _ => Ok(()),
+    }
+}
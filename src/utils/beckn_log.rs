@@ -0,0 +1,51 @@
+use crate::db::beckn_messages::{store_beckn_message, NewBecknMessage};
+use crate::utils::redact::redact_pii;
+use serde_json::Value;
+use sqlx::PgPool;
+use std::time::Duration;
+use tracing::error;
+
+pub const DIRECTION_OUTGOING: &str = "outgoing";
+pub const DIRECTION_INCOMING: &str = "incoming";
+
+pub struct BecknExchange<'a> {
+    pub direction: &'static str,
+    /// Used when the payload has no `context.action` (e.g. unparseable bodies).
+    pub fallback_action: &'a str,
+    pub url: Option<&'a str>,
+    pub payload: Option<&'a Value>,
+    pub http_status: Option<u16>,
+    pub latency: Duration,
+    pub error: Option<String>,
+}
+
+/// Records a Beckn request/callback in `beckn_messages` without holding up the caller.
+pub fn log_beckn_exchange(db_pool: &PgPool, exchange: BecknExchange) {
+    let context = exchange.payload.and_then(|p| p.get("context"));
+    let context_field = |name: &str| {
+        context
+            .and_then(|c| c.get(name))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+    };
+
+    let message = NewBecknMessage {
+        direction: exchange.direction,
+        action: context_field("action").unwrap_or_else(|| exchange.fallback_action.to_string()),
+        transaction_id: context_field("transaction_id").unwrap_or_else(|| "unknown".to_string()),
+        message_id: context_field("message_id"),
+        bpp_id: context_field("bpp_id"),
+        url: exchange.url.map(|u| u.to_string()),
+        http_status: exchange.http_status.map(i32::from),
+        latency_ms: exchange.latency.as_millis() as i64,
+        error: exchange.error,
+        payload: exchange.payload.map(redact_pii),
+    };
+
+    let db_pool = db_pool.clone();
+    tokio::spawn(async move {
+        if let Err(e) = store_beckn_message(&db_pool, message).await {
+            error!("❌ Failed to store beckn message: {:?}", e);
+        }
+    });
+}
@@ -0,0 +1,91 @@
+use crate::models::webhook::WebhookPayload;
+use crate::state::{AppState, OnSearchResponse};
+use redis::AsyncCommands;
+use tokio::sync::oneshot::{channel, Receiver};
+use tokio::time::{timeout, Duration};
+use tracing::{error, info};
+
+pub const RESPONSE_CHANNEL_PREFIX: &str = "beckn:response:";
+
+pub enum WaitError {
+    Timeout,
+    ChannelClosed,
+}
+
+pub fn correlation_key(txn_id: &str, msg_id: &str) -> String {
+    format!("{}:{}", txn_id, msg_id)
+}
+
+pub fn response_channel(key: &str) -> String {
+    format!("{}{}", RESPONSE_CHANNEL_PREFIX, key)
+}
+
+/// Registers a local waiter; the response listener forwards the matching
+/// callback to it no matter which replica received the webhook.
+pub fn register_waiter(app_state: &AppState, key: &str) -> Receiver<OnSearchResponse> {
+    let (tx, rx) = channel();
+    app_state
+        .shared_state
+        .pending_searches
+        .insert(key.to_string(), tx);
+    rx
+}
+
+pub fn cancel_waiter(app_state: &AppState, key: &str) {
+    app_state.shared_state.pending_searches.remove(key);
+}
+
+pub async fn wait_for_response(
+    app_state: &AppState,
+    key: &str,
+    rx: Receiver<OnSearchResponse>,
+    wait: Duration,
+) -> Result<OnSearchResponse, WaitError> {
+    match timeout(wait, rx).await {
+        Ok(Ok(payload)) => Ok(payload),
+        Ok(Err(_recv_err)) => Err(WaitError::ChannelClosed),
+        Err(_elapsed) => {
+            cancel_waiter(app_state, key);
+            Err(WaitError::Timeout)
+        }
+    }
+}
+
+/// Publishes an `on_*` callback so whichever instance is waiting on it can pick it up.
+pub async fn publish_response(
+    app_state: &AppState,
+    action: &str,
+    payload: &WebhookPayload,
+    txn_id: &str,
+    msg_id: &str,
+) {
+    let key = correlation_key(txn_id, msg_id);
+
+    let data = match serde_json::to_string(payload) {
+        Ok(d) => d,
+        Err(e) => {
+            info!("❌ Failed to serialize payload: {:?}", e);
+            return;
+        }
+    };
+
+    let mut conn = match app_state.redis_pool.get().await {
+        Ok(c) => c,
+        Err(e) => {
+            error!("❌ Failed to get Redis connection from pool: {:?}", e);
+            return;
+        }
+    };
+
+    match conn
+        .publish::<_, _, i64>(response_channel(&key), data)
+        .await
+    {
+        Ok(0) => info!(
+            "⚠️ No listener for {} response, transaction_id = {}",
+            action, txn_id
+        ),
+        Ok(_) => info!("✅ Published {} for transaction_id = {}", action, txn_id),
+        Err(e) => error!("❌ Failed to publish {} response: {:?}", action, e),
+    }
+}
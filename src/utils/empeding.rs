@@ -1,8 +1,9 @@
-use crate::config::AppConfig;
-use crate::config::MetaDataMatch;
-use serde_json::Value;
+use crate::config::{AppConfig, MatchMode};
+use crate::utils::match_modes::evaluate_field;
+use crate::utils::match_rules::MatchScoreRules;
+use serde::Serialize;
+use serde_json::{json, Value};
 use std::collections::HashMap;
-use std::fs;
 use strsim::jaro_winkler;
 
 pub fn cached_jaro(
@@ -25,24 +26,11 @@ fn weighted_push(parts: &mut Vec<String>, text: &str, weight: usize) {
     }
 }
 
-fn load_match_score_config(path: &str) -> Vec<MetaDataMatch> {
-    let data = fs::read_to_string(path).expect("Failed to read match_score.json");
-
-    #[derive(serde::Deserialize)]
-    struct Wrapper {
-        match_score: Vec<MetaDataMatch>,
-    }
-
-    let wrapper: Wrapper = serde_json::from_str(&data).expect("Failed to parse match_score.json");
-    wrapper.match_score
-}
-
-pub fn profile_text_for_embedding(profile: &Value, config: &AppConfig) -> String {
+pub fn profile_text_for_embedding(profile: &Value, rules: &MatchScoreRules) -> String {
     let mut parts = Vec::new();
-    let match_score = load_match_score_config(&config.match_score.config_path.as_str());
 
-    for field in &match_score {
-        if let crate::config::MatchMode::Embed = field.match_mode {
+    for field in &rules.fields {
+        if field.match_mode == MatchMode::Embed || field.embed {
             if let Some(value) = profile.pointer(&field.profile_path) {
                 let weight = field.weight.unwrap_or(1);
                 if field.is_array {
@@ -68,13 +56,11 @@ pub fn profile_text_for_embedding(profile: &Value, config: &AppConfig) -> String
     parts.join(" ")
 }
 
-pub fn job_text_for_embedding(job: &Value, config: &AppConfig) -> String {
-    let match_score = load_match_score_config(&config.match_score.config_path.as_str());
-
+pub fn job_text_for_embedding(job: &Value, rules: &MatchScoreRules) -> String {
     let mut parts = Vec::new();
 
-    for field in &match_score {
-        if let crate::config::MatchMode::Embed = field.match_mode {
+    for field in &rules.fields {
+        if field.match_mode == MatchMode::Embed || field.embed {
             if let Some(value) = job.pointer(&field.job_path) {
                 let weight = field.weight.unwrap_or(1);
                 if field.is_array {
@@ -103,7 +89,39 @@ pub fn cosine_similarity_with_norm(vec_a: &[f32], vec_b: &[f32], norm_a: f32, no
     dot_product / (norm_a * norm_b)
 }
 
-/// Compute final match score combining embedding cosine and manual numeric fields
+/// Why a pair scored what it did; stored in `job_profile_matches.score_breakdown`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoreBreakdown {
+    // Final 0..1 score; keeps the key older breakdowns used
+    pub cosine_score: f32,
+    pub base_cosine: f32,
+    pub business_logic: bool,
+    pub rules: Vec<RuleBreakdown>,
+    pub mismatches: usize,
+    pub mismatch_multiplier: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleBreakdown {
+    pub name: String,
+    pub match_mode: MatchMode,
+    pub outcome: RuleOutcome,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fit: Option<f32>,
+    pub multiplier: f32,
+    pub profile_value: Option<Value>,
+    pub job_value: Option<Value>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleOutcome {
+    Matched,
+    Mismatched,
+    MissingInProfile,
+}
+
+/// Compute final match score combining embedding cosine and the per-field match rules
 pub fn compute_empeding_match_score(
     profile_emb: &[f32],
     profile_norm: f32,
@@ -112,108 +130,89 @@ pub fn compute_empeding_match_score(
     profile_meta: &Value,
     job_meta: &Value,
     config: &AppConfig,
+    rules: &MatchScoreRules,
     string_sim_cache: &mut HashMap<(String, String), f32>,
-) -> f32 {
-    // info!("🔍 Computing match score...");
-    let match_score = load_match_score_config(&config.match_score.config_path.as_str());
-
-    // Base cosine similarity using precomputed norms
-    let mut score = cosine_similarity_with_norm(profile_emb, job_emb, profile_norm, job_norm);
-    // let base_score = score;
-    // warn!("🧮 Base cosine similarity score: {:.4}", base_score);
-
-    if !config.match_score.apply_business_logic {
-        if score.is_nan() {
-            score = 0.0;
-        }
-        return score.clamp(0.0, 1.0);
-    }
-
-    let mut mismatches = 0;
-
-    for field in &match_score {
-        let profile_val = profile_meta.pointer(&field.profile_path);
-        let job_val = job_meta.pointer(&field.job_path);
-
-        if job_val.is_some() && (profile_val.is_none() || profile_val == Some(&Value::Null)) {
-            score *= field.penalty;
-            mismatches += 1;
-            // warn!(
-            //     "⚠️ {} present in job but missing in profile → applied penalty {:.2}, score now {:.4}",
-            //     field.name, field.penalty, score
-            // );
-        }
-
-        match field.match_mode {
-            crate::config::MatchMode::Embed => {
-                if field.name == "role" || field.name == "industry" {
-                    let profile_str = profile_val.and_then(|v| v.as_str()).unwrap_or_default();
-                    let job_str = job_val.and_then(|v| v.as_str()).unwrap_or_default();
-
-                    if !profile_str.is_empty() && !job_str.is_empty() {
-                        let sim = cached_jaro(profile_str, job_str, string_sim_cache);
-                        if sim < 0.8 {
-                            score *= field.penalty;
-                            mismatches += 1;
-                            // warn!(
-                            //     "⚠️ {} similarity low ({:.2}) → applied penalty {:.2}, score now {:.4}",
-                            //     field.name, sim, field.penalty, score
-                            // );
-                        } else {
-                            // warn!(
-                            //     "✅ {} similarity good ({:.2}) → no penalty applied",
-                            //     field.name, sim
-                            // );
-                        }
-                    }
-                }
+) -> (f32, ScoreBreakdown) {
+    let base_cosine = cosine_similarity_with_norm(profile_emb, job_emb, profile_norm, job_norm);
+    let mut score = base_cosine;
+
+    let mut breakdown = ScoreBreakdown {
+        cosine_score: 0.0,
+        base_cosine: if base_cosine.is_nan() {
+            0.0
+        } else {
+            base_cosine
+        },
+        business_logic: config.match_score.apply_business_logic,
+        rules: Vec::new(),
+        mismatches: 0,
+        mismatch_multiplier: 1.0,
+    };
+
+    if config.match_score.apply_business_logic {
+        for field in &rules.fields {
+            let profile_val = profile_meta.pointer(&field.profile_path);
+            let job_val = job_meta.pointer(&field.job_path);
+
+            let job_value = || match field.match_mode {
+                MatchMode::Manual => Some(json!({
+                    "min": field.job_path_min.as_ref().and_then(|p| job_meta.pointer(p)),
+                    "max": field.job_path_max.as_ref().and_then(|p| job_meta.pointer(p)),
+                })),
+                _ => job_val.cloned(),
+            };
+
+            if job_val.is_some() && (profile_val.is_none() || profile_val == Some(&Value::Null)) {
+                score *= field.penalty;
+                breakdown.mismatches += 1;
+                breakdown.rules.push(RuleBreakdown {
+                    name: field.name.clone(),
+                    match_mode: field.match_mode.clone(),
+                    outcome: RuleOutcome::MissingInProfile,
+                    fit: None,
+                    multiplier: field.penalty,
+                    profile_value: None,
+                    job_value: job_value(),
+                });
             }
-            crate::config::MatchMode::Manual => {
-                let job_min = field
-                    .job_path_min
-                    .as_ref()
-                    .and_then(|p| job_meta.pointer(p));
-                let job_max = field
-                    .job_path_max
-                    .as_ref()
-                    .and_then(|p| job_meta.pointer(p));
-
-                if let (Some(profile_val), Some(job_min), Some(job_max)) =
-                    (profile_val, job_min, job_max)
-                {
-                    if let (Some(p), Some(min), Some(max)) =
-                        (profile_val.as_f64(), job_min.as_f64(), job_max.as_f64())
-                    {
-                        if p < min || p > max {
-                            score *= field.penalty;
-                            mismatches += 1;
-                            // warn!(
-                            //     "⚠️ {} out of range ({} not in [{}, {}]) → applied penalty {:.2}, score now {:.4}",
-                            //     field.name, p, min, max, field.penalty, score
-                            // );
-                        } else if let Some(bonus) = field.bonus {
-                            score *= bonus;
-                            // warn!(
-                            //     "✅ {} in range ({} in [{}, {}]) → applied bonus {:.2}, score now {:.4}",
-                            //     field.name, p, min, max, bonus, score
-                            // );
-                        }
-                    }
+
+            if let Some(outcome) =
+                evaluate_field(field, profile_val, job_val, job_meta, string_sim_cache)
+            {
+                score *= outcome.multiplier;
+                if !outcome.passed {
+                    breakdown.mismatches += 1;
                 }
+                breakdown.rules.push(RuleBreakdown {
+                    name: field.name.clone(),
+                    match_mode: field.match_mode.clone(),
+                    outcome: if outcome.passed {
+                        RuleOutcome::Matched
+                    } else {
+                        RuleOutcome::Mismatched
+                    },
+                    fit: Some(outcome.fit),
+                    multiplier: outcome.multiplier,
+                    profile_value: profile_val.cloned(),
+                    job_value: job_value(),
+                });
             }
         }
-    }
 
-    match mismatches {
-        2 => score *= 0.85,
-        3..=usize::MAX => score *= 0.7,
-        _ => {}
+        breakdown.mismatch_multiplier = match breakdown.mismatches {
+            2 => 0.85,
+            3..=usize::MAX => 0.7,
+            _ => 1.0,
+        };
+        score *= breakdown.mismatch_multiplier;
     }
 
     if score.is_nan() {
         score = 0.0;
-        // warn!("🚫 NaN detected — setting score to 0.0");
     }
 
-    score.clamp(0.0, 1.0)
+    let score = score.clamp(0.0, 1.0);
+    breakdown.cosine_score = score;
+
+    (score, breakdown)
 }
@@ -1,5 +1,9 @@
+use crate::state::AppState;
+use crate::utils::beckn_log::{log_beckn_exchange, BecknExchange, DIRECTION_OUTGOING};
+use crate::utils::signing::build_authorization_header;
 use reqwest::{header, Client};
 use serde_json::Value;
+use std::time::Instant;
 use tracing::{error, info};
 
 pub async fn post_json(
@@ -46,3 +50,82 @@ pub async fn get_json(url: &str, headers: header::HeaderMap) -> anyhow::Result<V
         Err(anyhow::anyhow!("Failed with status {}: {}", status, body))
     }
 }
+
+pub async fn post_json_for_value(url: &str, payload: Value) -> anyhow::Result<Value> {
+    let client = Client::new();
+
+    info!("Sending POST request to {}", url);
+
+    let res = client.post(url).json(&payload).send().await?;
+
+    if res.status().is_success() {
+        Ok(res.json::<Value>().await?)
+    } else {
+        let status = res.status();
+        let body = res.text().await?;
+        error!("❌ Error response: status={}, body={}", status, body);
+        Err(anyhow::anyhow!("Failed with status {}: {}", status, body))
+    }
+}
+
+/// Posts a Beckn payload, attaching a signed `Authorization` header when signing is enabled.
+/// Every call is recorded in the Beckn transaction log.
+pub async fn post_beckn_json(
+    app_state: &AppState,
+    url: &str,
+    payload: Value,
+) -> anyhow::Result<()> {
+    let config = &app_state.config;
+    let mut headers = header::HeaderMap::new();
+
+    if config.signing.enabled {
+        let body = serde_json::to_vec(&payload)?;
+        let authorization = build_authorization_header(config, &body)?;
+        headers.insert(
+            header::AUTHORIZATION,
+            header::HeaderValue::from_str(&authorization)?,
+        );
+    }
+
+    info!("Sending POST request to {}", url);
+
+    let started = Instant::now();
+    let response = Client::new()
+        .post(url)
+        .json(&payload)
+        .headers(headers)
+        .send()
+        .await;
+
+    let (http_status, result) = match response {
+        Ok(res) if res.status().is_success() => {
+            info!("✅ OK response");
+            (Some(res.status().as_u16()), Ok(()))
+        }
+        Ok(res) => {
+            let status = res.status();
+            let body = res.text().await.unwrap_or_default();
+            error!("❌ Error response: status={}, body={}", status, body);
+            (
+                Some(status.as_u16()),
+                Err(anyhow::anyhow!("Failed with status {}: {}", status, body)),
+            )
+        }
+        Err(e) => (None, Err(e.into())),
+    };
+
+    log_beckn_exchange(
+        &app_state.db_pool,
+        BecknExchange {
+            direction: DIRECTION_OUTGOING,
+            fallback_action: url.rsplit('/').next().unwrap_or_default(),
+            url: Some(url),
+            payload: Some(&payload),
+            http_status,
+            latency: started.elapsed(),
+            error: result.as_ref().err().map(|e| e.to_string()),
+        },
+    );
+
+    result
+}
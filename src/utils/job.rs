@@ -1,8 +1,7 @@
 use crate::db::job::{batch_update_job_embeddings, fetch_jobs_pending_embedding};
-use crate::services::empeding::{EmbeddingService, GcpEmbeddingService};
 use crate::state::AppState;
 use crate::utils::empeding::job_text_for_embedding;
-use crate::vector::index_store::save_faiss;
+use crate::vector::index_store::{rebuild_faiss_from_db, save_faiss};
 use std::sync::Arc;
 use tracing::{error, info};
 
@@ -10,8 +9,6 @@ pub async fn update_embeddings_for_bpp(
     app_state: &Arc<AppState>,
     bpp_id: &str,
 ) -> Result<(), anyhow::Error> {
-    let embedding_service = GcpEmbeddingService;
-
     let mut redis = app_state.redis_pool.get().await.map_err(|e| {
         error!("Redis connection failed: {}", e);
         e
@@ -35,46 +32,53 @@ pub async fn update_embeddings_for_bpp(
         bpp_id
     );
 
-    let batch_size = 20;
+    let batch_size = 20 * app_state.embedding.batch_options().concurrency.max(1);
+
+    let rules = app_state.match_rules.current();
+    let rules_hash = rules.embedding_inputs_hash();
 
     for chunk in jobs.chunks(batch_size) {
         let mut updates: Vec<(uuid::Uuid, Vec<f32>)> = Vec::new();
 
-        for job in chunk {
-            let beckn = match &job.beckn_structure {
-                Some(b) => b,
-                None => continue,
-            };
+        let pending: Vec<(&_, String)> = chunk
+            .iter()
+            .filter_map(|job| {
+                let beckn = job.beckn_structure.as_ref()?;
+                Some((job, job_text_for_embedding(beckn, &rules)))
+            })
+            .collect();
 
-            let text = job_text_for_embedding(beckn, &app_state.config);
-            info!(
-                "Generating embedding for job_id={} with text length={}",
-                job.job_id,
-                text.len()
-            );
+        let texts: Vec<&str> = pending.iter().map(|(_, text)| text.as_str()).collect();
+        let embeddings = app_state.embedding.get_embeddings(&texts, &mut redis).await;
 
-            match embedding_service
-                .get_embedding(&text, &mut redis, app_state)
-                .await
-            {
-                Ok(embedding) if !embedding.is_empty() => {
+        for ((job, _), embedding) in pending.iter().zip(embeddings) {
+            match embedding {
+                Some(embedding) if !embedding.is_empty() => {
                     updates.push((job.id, embedding));
                 }
-                Err(e) => {
-                    error!("Embedding failed for job_id={}: {}", job.job_id, e);
-                }
                 _ => {
-                    error!("Empty embedding for job_id={}", job.job_id);
+                    error!("Embedding failed for job_id={}", job.job_id);
                 }
             }
         }
 
         if !updates.is_empty() {
-            if let Err(e) = batch_update_job_embeddings(&app_state.db_pool, &updates).await {
+            if let Err(e) = batch_update_job_embeddings(
+                &app_state.db_pool,
+                &updates,
+                app_state.embedding.model(),
+                app_state.embedding.dimension() as i32,
+                &rules_hash,
+            )
+            .await
+            {
                 error!("Batch embedding update failed: {}", e);
                 continue;
             }
             let faiss = app_state.faiss.read().await;
+            if !faiss.is_trained().await {
+                continue;
+            }
             for (job_id, embedding) in updates {
                 if let Err(e) = faiss.upsert(job_id, embedding).await {
                     error!("Failed to upsert job_id={} in FAISS: {}", job_id, e);
@@ -83,9 +87,20 @@ pub async fn update_embeddings_for_bpp(
         }
     }
 
-    let faiss = app_state.faiss.read().await;
-    if let Err(e) = save_faiss(&faiss).await {
-        error!("Failed to save FAISS index: {}", e);
+    let trained = app_state.faiss.read().await.is_trained().await;
+    if !trained {
+        // IVF/PQ indexes need vectors to train on; the first crawl provides them.
+        info!("FAISS index is untrained, rebuilding now that embeddings exist");
+        if let Err(e) =
+            rebuild_faiss_from_db(app_state, app_state.embedding.dimension() as u32, None).await
+        {
+            error!("Failed to train and rebuild FAISS index: {}", e);
+        }
+    } else {
+        let faiss = app_state.faiss.read().await;
+        if let Err(e) = save_faiss(&faiss).await {
+            error!("Failed to save FAISS index: {}", e);
+        }
     }
 
     info!("Embedding generation completed (bpp_id={})", bpp_id);
@@ -0,0 +1,276 @@
+use crate::config::{VectorIndexType, VectorMetric};
+use crate::db::{
+    job::fetch_jobs_by_ids,
+    match_score::{
+        fetch_all_profiles, mark_jobs_matched, mark_profiles_matched, JobLiteRow, ProfileLiteRow,
+    },
+    profiles::{fetch_profiles_by_ids, ProfileRow},
+};
+use crate::services::match_score::ensure_profile_embeddings;
+use crate::state::AppState;
+use crate::utils::match_progress::MatchProgress;
+use crate::utils::match_score::score_pairs;
+use crate::vector::faiss_service::{FaissService, IndexSpec};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tracing::{error, info};
+use uuid::Uuid;
+
+/// Exact in-memory index over the given profiles' current vectors, so new jobs
+/// can look up their nearest profiles the same way profiles look up jobs.
+/// Also returns how many profiles had no current vector and were left out.
+async fn build_profile_index(
+    app_state: &AppState,
+    profiles: &[ProfileRow],
+) -> Result<(FaissService, usize), Box<dyn std::error::Error + Send + Sync>> {
+    let model = app_state.embedding.model();
+    let dimension = app_state.embedding.dimension();
+
+    let mut vector = app_state.config.vector.clone();
+    vector.index_type = VectorIndexType::Flat;
+    vector.index_factory = None;
+
+    let spec = IndexSpec {
+        factory: "IDMap,Flat".to_string(),
+        metric: VectorMetric::InnerProduct,
+        dimension: dimension as u32,
+        embedding_model: model.to_string(),
+    };
+
+    let index = FaissService::new(&vector, spec)?;
+    let mut unindexed = 0;
+    for profile in profiles {
+        match profile.current_embedding(model, dimension) {
+            Some(embedding) => index.upsert(profile.id, embedding.to_vec()).await?,
+            None => unindexed += 1,
+        }
+    }
+
+    Ok((index, unindexed))
+}
+
+/// Scores each new job against only its `candidate_top_n` nearest profiles.
+pub async fn process_job_candidates(
+    app_state: &Arc<AppState>,
+    progress: &Arc<MatchProgress>,
+    new_jobs: Vec<JobLiteRow>,
+) {
+    if new_jobs.is_empty() {
+        return;
+    }
+
+    let top_n = app_state.config.cron.compute_match_scores.candidate_top_n;
+
+    let mut profiles = match fetch_all_profiles(&app_state.db_pool).await {
+        Ok(p) => p,
+        Err(e) => {
+            error!("failed to fetch profiles: {:?}", e);
+            return;
+        }
+    };
+
+    if profiles.is_empty() {
+        info!(
+            "⏭️ skipping match scoring: {} new jobs but no profiles available",
+            new_jobs.len()
+        );
+        return;
+    }
+
+    let embedded = ensure_profile_embeddings(app_state, &mut profiles).await;
+    if embedded > 0 {
+        info!("🧮 embedded {} profiles before scoring", embedded);
+    }
+
+    let (profile_index, unindexed) = match build_profile_index(app_state, &profiles).await {
+        Ok(built) => built,
+        Err(e) => {
+            error!("failed to build profile index: {}", e);
+            return;
+        }
+    };
+
+    let job_ids: Vec<Uuid> = new_jobs.iter().map(|j| j.id).collect();
+    let jobs = match fetch_jobs_by_ids(&app_state.db_pool, &job_ids).await {
+        Ok(j) => j,
+        Err(e) => {
+            error!("failed to fetch jobs: {:?}", e);
+            return;
+        }
+    };
+
+    let by_id: HashMap<Uuid, usize> = profiles
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (p.id, i))
+        .collect();
+    let hashes: HashMap<Uuid, String> = new_jobs.into_iter().map(|j| (j.id, j.hash)).collect();
+
+    info!(
+        "🎯 scoring {} new jobs against their top {} of {} profiles",
+        jobs.len(),
+        top_n,
+        profiles.len()
+    );
+
+    let mut pairs: Vec<(usize, usize)> = Vec::new();
+    let mut searched: Vec<(usize, Uuid, String)> = Vec::with_capacity(jobs.len());
+
+    for (job_idx, job) in jobs.iter().enumerate() {
+        let Some(embedding) = job.embedding.clone() else {
+            continue;
+        };
+
+        let candidates = match profile_index.search(embedding, top_n).await {
+            Ok(c) => c,
+            Err(e) => {
+                error!("profile search failed for job {}: {}", job.id, e);
+                continue;
+            }
+        };
+
+        pairs.extend(
+            candidates
+                .iter()
+                .filter_map(|(profile_id, _)| by_id.get(profile_id))
+                .map(|&profile_idx| (job_idx, profile_idx)),
+        );
+
+        if let Some(hash) = hashes.get(&job.id) {
+            searched.push((job_idx, job.id, hash.clone()));
+        }
+    }
+
+    progress.add_total(app_state, "new_jobs", pairs.len()).await;
+    let failed = score_pairs(app_state, progress, jobs, profiles, pairs, "new_job").await;
+
+    // Jobs with a pair that wasn't written stay unmatched and are retried, and
+    // so does every job while some profile couldn't be embedded, since that
+    // profile was never a candidate.
+    if unindexed > 0 {
+        info!(
+            "⚠️ {} profiles have no current embedding, leaving {} jobs unmatched for retry",
+            unindexed,
+            searched.len()
+        );
+        return;
+    }
+
+    let failed_jobs: HashSet<usize> = failed.into_iter().map(|(job_idx, _)| job_idx).collect();
+    let matched: Vec<(Uuid, String)> = searched
+        .into_iter()
+        .filter(|(job_idx, _, _)| !failed_jobs.contains(job_idx))
+        .map(|(_, id, hash)| (id, hash))
+        .collect();
+
+    info!("✅ finished {} new jobs", matched.len());
+
+    if let Err(e) = mark_jobs_matched(&app_state.db_pool, &matched).await {
+        error!("failed to mark jobs matched: {:?}", e);
+    }
+}
+
+/// Scores each new profile against only its `candidate_top_n` nearest jobs.
+pub async fn process_profile_candidates(
+    app_state: &Arc<AppState>,
+    progress: &Arc<MatchProgress>,
+    new_profiles: Vec<ProfileLiteRow>,
+) {
+    if new_profiles.is_empty() {
+        return;
+    }
+
+    let top_n = app_state.config.cron.compute_match_scores.candidate_top_n;
+    let model = app_state.embedding.model();
+    let dimension = app_state.embedding.dimension();
+
+    let profile_ids: Vec<Uuid> = new_profiles.iter().map(|p| p.id).collect();
+    let mut profiles = match fetch_profiles_by_ids(&app_state.db_pool, &profile_ids).await {
+        Ok(p) => p,
+        Err(e) => {
+            error!("failed to fetch profiles: {:?}", e);
+            return;
+        }
+    };
+
+    ensure_profile_embeddings(app_state, &mut profiles).await;
+
+    let hashes: HashMap<Uuid, String> = new_profiles.into_iter().map(|p| (p.id, p.hash)).collect();
+    let faiss = app_state.faiss.read().await.clone();
+
+    info!(
+        "🎯 scoring {} new profiles against their top {} jobs",
+        profiles.len(),
+        top_n
+    );
+
+    let mut candidates_by_profile: Vec<(usize, Vec<Uuid>)> = Vec::with_capacity(profiles.len());
+    let mut searched: Vec<(usize, Uuid, String)> = Vec::with_capacity(profiles.len());
+
+    for (profile_idx, profile) in profiles.iter().enumerate() {
+        let Some(embedding) = profile.current_embedding(model, dimension) else {
+            continue;
+        };
+
+        let candidates = match faiss.search(embedding.to_vec(), top_n).await {
+            Ok(c) => c,
+            Err(e) => {
+                error!("job search failed for profile {}: {}", profile.id, e);
+                continue;
+            }
+        };
+
+        candidates_by_profile.push((
+            profile_idx,
+            candidates.into_iter().map(|(id, _)| id).collect(),
+        ));
+        if let Some(hash) = hashes.get(&profile.id) {
+            searched.push((profile_idx, profile.id, hash.clone()));
+        }
+    }
+
+    // Profiles share most of their candidate jobs, so load each job once.
+    let mut job_ids: Vec<Uuid> = candidates_by_profile
+        .iter()
+        .flat_map(|(_, ids)| ids.iter().copied())
+        .collect();
+    job_ids.sort_unstable();
+    job_ids.dedup();
+
+    let jobs = match fetch_jobs_by_ids(&app_state.db_pool, &job_ids).await {
+        Ok(j) => j,
+        Err(e) => {
+            error!("failed to fetch candidate jobs: {:?}", e);
+            return;
+        }
+    };
+
+    let by_id: HashMap<Uuid, usize> = jobs.iter().enumerate().map(|(i, j)| (j.id, i)).collect();
+    let pairs: Vec<(usize, usize)> = candidates_by_profile
+        .iter()
+        .flat_map(|(profile_idx, ids)| {
+            ids.iter()
+                .filter_map(|id| by_id.get(id))
+                .map(move |&job_idx| (job_idx, *profile_idx))
+        })
+        .collect();
+
+    progress
+        .add_total(app_state, "new_profiles", pairs.len())
+        .await;
+    let failed = score_pairs(app_state, progress, jobs, profiles, pairs, "new_profile").await;
+
+    // Profiles with a pair that wasn't written stay unmatched and are retried.
+    let failed_profiles: HashSet<usize> = failed.into_iter().map(|(_, p)| p).collect();
+    let matched: Vec<(Uuid, String)> = searched
+        .into_iter()
+        .filter(|(profile_idx, _, _)| !failed_profiles.contains(profile_idx))
+        .map(|(_, id, hash)| (id, hash))
+        .collect();
+
+    info!("✅ finished {} new profiles", matched.len());
+
+    if let Err(e) = mark_profiles_matched(&app_state.db_pool, &matched).await {
+        error!("failed to mark profiles matched: {:?}", e);
+    }
+}
@@ -0,0 +1,333 @@
+use crate::config::{Comparison, MatchMode, MetaDataMatch, ScoreCurve};
+use crate::utils::empeding::cached_jaro;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// How one field scored: its fit in 0..1, the multiplier the curve turned it
+/// into, and whether it reached the cutoff (misses count as mismatches).
+#[derive(Debug, Clone, Copy)]
+pub struct FieldOutcome {
+    pub fit: f32,
+    pub multiplier: f32,
+    pub passed: bool,
+}
+
+/// Scores one rule against a profile/job pair. `None` when the rule doesn't
+/// apply, e.g. a value is absent or not comparable.
+pub fn evaluate_field(
+    field: &MetaDataMatch,
+    profile_val: Option<&Value>,
+    job_val: Option<&Value>,
+    job_meta: &Value,
+    string_sim_cache: &mut HashMap<(String, String), f32>,
+) -> Option<FieldOutcome> {
+    let profile_val = profile_val.filter(|v| !v.is_null())?;
+
+    let fit = match field.match_mode {
+        MatchMode::Embed => {
+            // Only fields with a cutoff get a string similarity check.
+            field.cutoff?;
+            let profile_str = profile_val.as_str().unwrap_or_default();
+            let job_str = job_val.and_then(|v| v.as_str()).unwrap_or_default();
+            if profile_str.is_empty() || job_str.is_empty() {
+                return None;
+            }
+            cached_jaro(profile_str, job_str, string_sim_cache)
+        }
+        MatchMode::Manual => {
+            let job_min = field
+                .job_path_min
+                .as_ref()
+                .and_then(|p| job_meta.pointer(p));
+            let job_max = field
+                .job_path_max
+                .as_ref()
+                .and_then(|p| job_meta.pointer(p));
+            let (p, min, max) = (
+                profile_val.as_f64()?,
+                job_min?.as_f64()?,
+                job_max?.as_f64()?,
+            );
+            if p < min || p > max {
+                0.0
+            } else {
+                1.0
+            }
+        }
+        MatchMode::Overlap => {
+            let profile_set = string_set(profile_val, &HashMap::new());
+            let job_set = string_set(job_val?, &HashMap::new());
+            if profile_set.is_empty() || job_set.is_empty() {
+                return None;
+            }
+            let shared = profile_set.intersection(&job_set).count();
+            let union = profile_set.union(&job_set).count();
+            shared as f32 / union as f32
+        }
+        MatchMode::Enum => {
+            let aliases = alias_map(&field.synonyms);
+            let profile_set = string_set(profile_val, &aliases);
+            let job_set = string_set(job_val?, &aliases);
+            if profile_set.is_empty() || job_set.is_empty() {
+                return None;
+            }
+            if profile_set.is_disjoint(&job_set) {
+                0.0
+            } else {
+                1.0
+            }
+        }
+        MatchMode::Threshold => {
+            let p = as_number(profile_val)?;
+            let j = as_number(job_val?)?;
+            let ratio = match field.comparison.as_ref()? {
+                Comparison::Gte if p >= j => 1.0,
+                Comparison::Lte if p <= j => 1.0,
+                // A shortfall is only a fraction of the bound when both are positive.
+                _ if p <= 0.0 || j <= 0.0 => 0.0,
+                Comparison::Gte => p / j,
+                Comparison::Lte => j / p,
+            };
+            (ratio as f32).clamp(0.0, 1.0)
+        }
+        MatchMode::Geo => {
+            let radius = field.radius_km?;
+            let from = geo_point(profile_val)?;
+            let to = geo_point(job_val?)?;
+            let distance = haversine_km(from, to);
+            if distance <= radius {
+                1.0
+            } else {
+                (radius / distance) as f32
+            }
+        }
+    };
+
+    let cutoff = field.cutoff.unwrap_or(match field.match_mode {
+        // Any shared value passes unless a cutoff is configured.
+        MatchMode::Overlap => f32::EPSILON,
+        _ => 1.0,
+    });
+    let passed = fit >= cutoff;
+    let bonus = field.bonus.unwrap_or(1.0);
+
+    let multiplier = match field.curve {
+        ScoreCurve::Step if passed => bonus,
+        ScoreCurve::Step => field.penalty,
+        ScoreCurve::Linear => field.penalty + (bonus - field.penalty) * fit,
+    };
+
+    Some(FieldOutcome {
+        fit,
+        multiplier,
+        passed,
+    })
+}
+
+fn alias_map(synonyms: &HashMap<String, Vec<String>>) -> HashMap<String, String> {
+    let mut aliases = HashMap::new();
+    for (canonical, names) in synonyms {
+        let canonical = normalize(canonical);
+        for name in names {
+            aliases.insert(normalize(name), canonical.clone());
+        }
+        aliases.insert(canonical.clone(), canonical);
+    }
+    aliases
+}
+
+fn normalize(s: &str) -> String {
+    s.trim().to_lowercase()
+}
+
+/// Lower-cased strings from a string, an array of strings, or a
+/// comma-separated string, mapped through `aliases`.
+fn string_set(value: &Value, aliases: &HashMap<String, String>) -> HashSet<String> {
+    let raw: Vec<&str> = match value {
+        Value::Array(items) => items.iter().filter_map(|v| v.as_str()).collect(),
+        Value::String(s) => s.split(',').collect(),
+        _ => Vec::new(),
+    };
+
+    raw.into_iter()
+        .map(normalize)
+        .filter(|s| !s.is_empty())
+        .map(|s| aliases.get(&s).cloned().unwrap_or(s))
+        .collect()
+}
+
+fn as_number(value: &Value) -> Option<f64> {
+    value
+        .as_f64()
+        .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+}
+
+/// Accepts "lat,lon" strings (Beckn gps) or objects with lat/lon, latitude/longitude or gps.
+fn geo_point(value: &Value) -> Option<(f64, f64)> {
+    match value {
+        Value::String(s) => {
+            let mut parts = s.split(',').map(|p| p.trim().parse::<f64>());
+            let lat = parts.next()?.ok()?;
+            let lon = parts.next()?.ok()?;
+            Some((lat, lon))
+        }
+        Value::Object(obj) => {
+            if let Some(gps) = obj.get("gps") {
+                return geo_point(gps);
+            }
+            let lat = obj.get("lat").or_else(|| obj.get("latitude"))?;
+            let lon = obj
+                .get("lon")
+                .or_else(|| obj.get("lng"))
+                .or_else(|| obj.get("longitude"))?;
+            Some((as_number(lat)?, as_number(lon)?))
+        }
+        _ => None,
+    }
+}
+
+fn haversine_km((lat1, lon1): (f64, f64), (lat2, lon2): (f64, f64)) -> f64 {
+    let d_lat = (lat2 - lat1).to_radians();
+    let d_lon = (lon2 - lon1).to_radians();
+    let a = (d_lat / 2.0).sin().powi(2)
+        + lat1.to_radians().cos() * lat2.to_radians().cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rule(extra: Value) -> MetaDataMatch {
+        let mut rule = json!({"name": "f", "profile_path": "/f", "penalty": 0.5});
+        rule.as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(rule).unwrap()
+    }
+
+    fn evaluate(field: &MetaDataMatch, profile: Value, job: Value) -> Option<FieldOutcome> {
+        evaluate_field(
+            field,
+            Some(&profile),
+            Some(&job),
+            &json!({}),
+            &mut HashMap::new(),
+        )
+    }
+
+    #[test]
+    fn threshold_scales_a_shortfall() {
+        let field = rule(json!({"match_mode": "threshold", "comparison": "gte"}));
+
+        let outcome = evaluate(&field, json!(20), json!("25")).unwrap();
+        assert!((outcome.fit - 0.8).abs() < 1e-6);
+        assert!(!outcome.passed);
+        assert!(evaluate(&field, json!(30), json!(25)).unwrap().passed);
+    }
+
+    #[test]
+    fn threshold_fails_non_positive_bounds() {
+        let gte = rule(json!({"match_mode": "threshold", "comparison": "gte"}));
+        let lte = rule(json!({"match_mode": "threshold", "comparison": "lte"}));
+
+        // -5 / -2 would otherwise clamp to a full pass.
+        assert_eq!(evaluate(&gte, json!(-5), json!(-2)).unwrap().fit, 0.0);
+        assert_eq!(evaluate(&lte, json!(5), json!(-1)).unwrap().fit, 0.0);
+        assert_eq!(evaluate(&lte, json!(0), json!(10)).unwrap().fit, 1.0);
+    }
+
+    #[test]
+    fn geo_scales_by_haversine_distance() {
+        let bengaluru = (12.9716, 77.5946);
+        let mysuru = (12.2958, 76.6394);
+        let distance = haversine_km(bengaluru, mysuru);
+        assert!((125.0..130.0).contains(&distance), "{}", distance);
+
+        let near = rule(json!({"match_mode": "geo", "radius_km": 200.0}));
+        let far = rule(json!({"match_mode": "geo", "radius_km": 64.0}));
+        let job = json!({"gps": "12.2958, 76.6394"});
+        let profile = json!({"lat": 12.9716, "lng": 77.5946});
+
+        assert!(
+            evaluate(&near, profile.clone(), job.clone())
+                .unwrap()
+                .passed
+        );
+        let outcome = evaluate(&far, profile, job).unwrap();
+        assert!((outcome.fit as f64 - 64.0 / distance).abs() < 1e-6);
+        assert!(!outcome.passed);
+    }
+
+    #[test]
+    fn enum_matches_through_synonyms() {
+        let field = rule(json!({
+            "match_mode": "enum",
+            "synonyms": {"driver": ["Chauffeur", "cab driver"]}
+        }));
+
+        assert!(
+            evaluate(&field, json!(" chauffeur"), json!(["Driver"]))
+                .unwrap()
+                .passed
+        );
+        assert!(
+            evaluate(&field, json!("cab driver, cook"), json!("chauffeur"))
+                .unwrap()
+                .passed
+        );
+        assert!(
+            !evaluate(&field, json!("cook"), json!("driver"))
+                .unwrap()
+                .passed
+        );
+    }
+
+    #[test]
+    fn linear_curve_interpolates_below_cutoff() {
+        let field = rule(json!({
+            "match_mode": "overlap",
+            "curve": "linear",
+            "cutoff": 0.5,
+            "bonus": 2.0
+        }));
+
+        let outcome = evaluate(&field, json!(["a", "b"]), json!("b, c")).unwrap();
+        assert!((outcome.fit - 1.0 / 3.0).abs() < 1e-6);
+        assert!((outcome.multiplier - 1.0).abs() < 1e-6);
+        assert!(!outcome.passed);
+    }
+
+    #[test]
+    fn step_curve_uses_bonus_or_penalty() {
+        let field = rule(json!({"match_mode": "overlap", "bonus": 1.5}));
+
+        assert_eq!(
+            evaluate(&field, json!("a"), json!("a, b"))
+                .unwrap()
+                .multiplier,
+            1.5
+        );
+        assert_eq!(
+            evaluate(&field, json!("a"), json!("b")).unwrap().multiplier,
+            0.5
+        );
+    }
+
+    #[test]
+    fn null_or_absent_values_do_not_apply() {
+        let field = rule(json!({"match_mode": "threshold", "comparison": "gte"}));
+        let mut cache = HashMap::new();
+
+        assert!(evaluate(&field, Value::Null, json!(18)).is_none());
+        assert!(evaluate_field(&field, Some(&json!(20)), None, &json!({}), &mut cache).is_none());
+        assert!(evaluate(&field, json!("n/a"), json!(18)).is_none());
+
+        let manual = rule(json!({"match_mode": "manual", "job_path_min": "/min"}));
+        let meta = json!({"min": 1});
+        assert!(evaluate_field(&manual, Some(&json!(2)), None, &meta, &mut cache).is_none());
+    }
+}
@@ -0,0 +1,130 @@
+use deadpool_redis::redis::cmd;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+use tokio::sync::Mutex;
+use tracing::error;
+
+use crate::state::AppState;
+
+const PROGRESS_KEY: &str = "match_score:progress";
+const PROGRESS_TTL_SECS: u64 = 86_400;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Pair counters for one match-score run, mirrored to a Redis hash so the
+/// admin API can report done/total and an ETA while the cron is working.
+pub struct MatchProgress {
+    started: Instant,
+    total: AtomicU64,
+    done: AtomicU64,
+    failed: AtomicU64,
+    // Serializes writes so a slower task can't overwrite newer counts.
+    write_lock: Mutex<()>,
+}
+
+impl MatchProgress {
+    pub async fn start(app_state: &AppState) -> Self {
+        let progress = Self {
+            started: Instant::now(),
+            total: AtomicU64::new(0),
+            done: AtomicU64::new(0),
+            failed: AtomicU64::new(0),
+            write_lock: Mutex::new(()),
+        };
+
+        if let Err(e) = clear_progress(app_state).await {
+            error!("Failed to reset match-score progress: {}", e);
+        }
+        progress
+            .write(
+                app_state,
+                vec![
+                    ("state", "running".to_string()),
+                    ("phase", "planning".to_string()),
+                    ("started_at", chrono::Utc::now().to_rfc3339()),
+                ],
+            )
+            .await;
+        progress
+    }
+
+    /// Adds a phase's pairs to the total as soon as they are known.
+    pub async fn add_total(&self, app_state: &AppState, phase: &str, pairs: usize) {
+        self.total.fetch_add(pairs as u64, Ordering::Relaxed);
+        self.write(app_state, vec![("phase", phase.to_string())])
+            .await;
+    }
+
+    pub async fn advance(&self, app_state: &AppState, done: usize, failed: usize) {
+        self.done.fetch_add(done as u64, Ordering::Relaxed);
+        self.failed.fetch_add(failed as u64, Ordering::Relaxed);
+        self.write(app_state, Vec::new()).await;
+    }
+
+    pub async fn finish(&self, app_state: &AppState) {
+        self.write(
+            app_state,
+            vec![
+                ("state", "completed".to_string()),
+                ("finished_at", chrono::Utc::now().to_rfc3339()),
+            ],
+        )
+        .await;
+    }
+
+    async fn write(&self, app_state: &AppState, mut fields: Vec<(&str, String)>) {
+        let _guard = self.write_lock.lock().await;
+
+        let total = self.total.load(Ordering::Relaxed);
+        let done = self.done.load(Ordering::Relaxed);
+        fields.push(("total", total.to_string()));
+        fields.push(("done", done.to_string()));
+        fields.push(("failed", self.failed.load(Ordering::Relaxed).to_string()));
+        fields.push(("updated_at", chrono::Utc::now().to_rfc3339()));
+
+        // Linear extrapolation from the pace so far.
+        if done > 0 && total >= done {
+            let elapsed = self.started.elapsed().as_secs_f64();
+            let eta = elapsed / done as f64 * (total - done) as f64;
+            fields.push(("eta_secs", format!("{:.0}", eta)));
+        }
+
+        if let Err(e) = set_progress(app_state, &fields).await {
+            error!("Failed to record match-score progress: {}", e);
+        }
+    }
+}
+
+pub async fn get_progress(app_state: &AppState) -> Result<HashMap<String, String>, BoxError> {
+    let mut conn = app_state.redis_pool.get().await?;
+    let progress: HashMap<String, String> = cmd("HGETALL")
+        .arg(PROGRESS_KEY)
+        .query_async(&mut conn)
+        .await?;
+    Ok(progress)
+}
+
+async fn clear_progress(app_state: &AppState) -> Result<(), BoxError> {
+    let mut conn = app_state.redis_pool.get().await?;
+    let _: () = cmd("DEL").arg(PROGRESS_KEY).query_async(&mut conn).await?;
+    Ok(())
+}
+
+async fn set_progress(app_state: &AppState, fields: &[(&str, String)]) -> Result<(), BoxError> {
+    let mut conn = app_state.redis_pool.get().await?;
+
+    let mut hset = cmd("HSET");
+    hset.arg(PROGRESS_KEY);
+    for (field, value) in fields {
+        hset.arg(*field).arg(value);
+    }
+    let _: () = hset.query_async(&mut conn).await?;
+
+    let _: () = cmd("EXPIRE")
+        .arg(PROGRESS_KEY)
+        .arg(PROGRESS_TTL_SECS)
+        .query_async(&mut conn)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,197 @@
+use crate::config::{MatchMode, MetaDataMatch};
+use crate::state::AppState;
+use crate::workers::reembed::start as start_reembed;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+use tracing::{error, info};
+
+/// Parsed and validated `match_score.json`.
+#[derive(Debug, Clone)]
+pub struct MatchScoreRules {
+    pub fields: Vec<MetaDataMatch>,
+}
+
+impl MatchScoreRules {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let data = std::fs::read_to_string(path).map_err(|e| format!("read {}: {}", path, e))?;
+
+        #[derive(Deserialize)]
+        struct Wrapper {
+            match_score: Vec<MetaDataMatch>,
+        }
+
+        let wrapper: Wrapper =
+            serde_json::from_str(&data).map_err(|e| format!("parse {}: {}", path, e))?;
+
+        let rules = Self {
+            fields: wrapper.match_score,
+        };
+        rules.validate()?;
+        Ok(rules)
+    }
+
+    /// Hash of everything that shapes the embed text, so stored vectors can be
+    /// told apart from ones the current rules would produce.
+    pub fn embedding_inputs_hash(&self) -> String {
+        use sha2::{Digest, Sha256};
+
+        let mut hasher = Sha256::new();
+        for field in &self.fields {
+            if field.match_mode == MatchMode::Embed || field.embed {
+                hasher.update(&field.profile_path);
+                hasher.update([0]);
+                hasher.update(&field.job_path);
+                hasher.update([0]);
+                hasher.update(field.weight.unwrap_or(1).to_le_bytes());
+                hasher.update([field.is_array as u8]);
+            }
+        }
+
+        hex::encode(hasher.finalize())
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.fields.is_empty() {
+            return Err("match_score must list at least one field".into());
+        }
+
+        let mut names = HashSet::new();
+        for field in &self.fields {
+            if field.name.trim().is_empty() {
+                return Err("match_score field with an empty name".into());
+            }
+            if !names.insert(field.name.as_str()) {
+                return Err(format!("duplicate match_score field '{}'", field.name));
+            }
+            if !field.profile_path.starts_with('/') {
+                return Err(format!(
+                    "'{}': profile_path must be a JSON pointer",
+                    field.name
+                ));
+            }
+            if !(field.penalty > 0.0 && field.penalty <= 1.0) {
+                return Err(format!("'{}': penalty must be in (0, 1]", field.name));
+            }
+            if field.bonus.is_some_and(|b| b <= 0.0) {
+                return Err(format!("'{}': bonus must be positive", field.name));
+            }
+            if field.weight == Some(0) {
+                return Err(format!("'{}': weight must be at least 1", field.name));
+            }
+
+            if field.cutoff.is_some_and(|c| !(0.0..=1.0).contains(&c)) {
+                return Err(format!("'{}': cutoff must be in [0, 1]", field.name));
+            }
+
+            match field.match_mode {
+                MatchMode::Embed | MatchMode::Overlap | MatchMode::Enum => {
+                    if !field.job_path.starts_with('/') {
+                        return Err(format!("'{}': job_path must be a JSON pointer", field.name));
+                    }
+                }
+                MatchMode::Threshold => {
+                    if !field.job_path.starts_with('/') || field.comparison.is_none() {
+                        return Err(format!(
+                            "'{}': threshold fields need job_path and comparison",
+                            field.name
+                        ));
+                    }
+                }
+                MatchMode::Geo => {
+                    if !field.job_path.starts_with('/') || !field.radius_km.is_some_and(|r| r > 0.0)
+                    {
+                        return Err(format!(
+                            "'{}': geo fields need job_path and a positive radius_km",
+                            field.name
+                        ));
+                    }
+                }
+                MatchMode::Manual => {
+                    let valid =
+                        |p: &Option<String>| p.as_deref().is_some_and(|p| p.starts_with('/'));
+                    if !valid(&field.job_path_min) || !valid(&field.job_path_max) {
+                        return Err(format!(
+                            "'{}': manual fields need job_path_min and job_path_max",
+                            field.name
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Current rules plus the file they came from; readers grab an `Arc` snapshot
+/// and a reload swaps it without blocking them.
+pub struct MatchRulesHandle {
+    path: String,
+    current: RwLock<Arc<MatchScoreRules>>,
+    loaded_mtime: RwLock<Option<SystemTime>>,
+}
+
+impl MatchRulesHandle {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let rules = MatchScoreRules::load(path)?;
+        Ok(Self {
+            path: path.to_string(),
+            current: RwLock::new(Arc::new(rules)),
+            loaded_mtime: RwLock::new(modified_at(path)),
+        })
+    }
+
+    pub fn current(&self) -> Arc<MatchScoreRules> {
+        self.current.read().unwrap().clone()
+    }
+
+    /// Reloads if the file changed since the last attempt. Invalid edits are
+    /// logged and the previous rules stay in place. Returns true when the
+    /// reload changed what goes into the embed text.
+    pub fn reload_if_changed(&self) -> bool {
+        let mtime = modified_at(&self.path);
+        if mtime.is_none() || mtime == *self.loaded_mtime.read().unwrap() {
+            return false;
+        }
+        *self.loaded_mtime.write().unwrap() = mtime;
+
+        match MatchScoreRules::load(&self.path) {
+            Ok(rules) => {
+                let count = rules.fields.len();
+                let embed_changed =
+                    rules.embedding_inputs_hash() != self.current().embedding_inputs_hash();
+                *self.current.write().unwrap() = Arc::new(rules);
+                info!("🔄 Reloaded {} ({} fields)", self.path, count);
+                embed_changed
+            }
+            Err(e) => {
+                error!(
+                    "❌ Rejected {} edit, keeping previous rules: {}",
+                    self.path, e
+                );
+                false
+            }
+        }
+    }
+}
+
+fn modified_at(path: &str) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Polls the rules file and re-embeds stored vectors when an edit changes
+/// the embed text, so they keep matching what new vectors are built from.
+pub fn spawn_watcher(app_state: Arc<AppState>, interval_secs: u64) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(interval_secs.max(1)));
+        loop {
+            ticker.tick().await;
+            if app_state.match_rules.reload_if_changed() {
+                info!("⚠️ Embed fields changed, re-embedding stored vectors");
+                tokio::spawn(start_reembed(app_state.clone()));
+            }
+        }
+    });
+}
@@ -1,24 +1,44 @@
 use crate::db::{
-    job::{fetch_job_by_id, JobRow},
+    job::{fetch_jobs_by_ids, JobRow},
     match_score::{
-        fetch_all_jobs, fetch_all_profiles, fetch_missing_matches, fetch_new_jobs,
-        fetch_new_profiles, fetch_stale_matches, upsert_match_score, JobLiteRow, ProfileLiteRow,
-        StaleMatchRow,
+        batch_upsert_match_scores, delete_match_scores, fetch_all_jobs, fetch_all_profiles,
+        fetch_missing_matches, fetch_new_jobs, fetch_new_profiles, fetch_stale_matches,
+        fetch_unmatched_jobs, fetch_unmatched_profiles, JobLiteRow, MatchScoreUpsert,
+        ProfileLiteRow,
     },
-    profiles::{fetch_profile_by_id, ProfileRow},
+    profiles::{fetch_profiles_by_ids, ProfileRow},
 };
-use crate::services::match_score::compute_match_score;
+use crate::services::match_score::{compute_match_score, ensure_profile_embeddings};
 use crate::state::AppState;
 use crate::utils::batching::chunk_vec;
 use crate::utils::logging::format_duration;
+use crate::utils::match_candidates::{process_job_candidates, process_profile_candidates};
+use crate::utils::match_progress::MatchProgress;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use std::time::Instant;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 use tracing::{error, info};
+use uuid::Uuid;
 
-pub async fn calculate_match_score(app_state: &AppState) {
+pub async fn calculate_match_score(app_state: &Arc<AppState>) {
     let start = Instant::now();
     info!("🔄 match-score cron started");
 
-    let new_jobs = match fetch_new_jobs(&app_state.db_pool).await {
+    let schedule = &app_state.config.cron.compute_match_scores;
+    let use_candidates = schedule.candidate_top_n > 0;
+    let new_jobs = if use_candidates {
+        fetch_unmatched_jobs(
+            &app_state.db_pool,
+            app_state.embedding.model(),
+            app_state.embedding.dimension() as i32,
+        )
+        .await
+    } else {
+        fetch_new_jobs(&app_state.db_pool).await
+    };
+    let new_jobs = match new_jobs {
         Ok(v) => v,
         Err(e) => {
             tracing::error!("failed to fetch new jobs: {:?}", e);
@@ -26,7 +46,12 @@ pub async fn calculate_match_score(app_state: &AppState) {
         }
     };
 
-    let new_profiles = match fetch_new_profiles(&app_state.db_pool).await {
+    let new_profiles = if use_candidates {
+        fetch_unmatched_profiles(&app_state.db_pool).await
+    } else {
+        fetch_new_profiles(&app_state.db_pool).await
+    };
+    let new_profiles = match new_profiles {
         Ok(v) => v,
         Err(e) => {
             tracing::error!("failed to fetch new profiles: {:?}", e);
@@ -49,17 +74,29 @@ pub async fn calculate_match_score(app_state: &AppState) {
         stale_matches.len()
     );
 
+    let progress = Arc::new(MatchProgress::start(app_state).await);
+
     /* ---------------- stale matches (batched) ---------------- */
 
-    let batch_size = app_state.config.cron.compute_match_scores.batch.max(1);
+    let batch_size = schedule.batch.max(1);
+    // Enough pairs per load to keep every worker busy.
+    let load_size = batch_size * schedule.concurrency.max(1);
 
     if !stale_matches.is_empty() {
-        let stale_batches = chunk_vec(stale_matches, batch_size);
+        progress
+            .add_total(app_state, "stale", stale_matches.len())
+            .await;
+
+        let stale_pairs: Vec<(Uuid, Uuid)> = stale_matches
+            .into_iter()
+            .map(|m| (m.job_id, m.profile_id))
+            .collect();
+        let stale_batches = chunk_vec(stale_pairs, load_size);
         let stale_total = stale_batches.len();
 
         info!(
             "🔁 recomputing stale matches in {} batches (batch_size={})",
-            stale_total, batch_size
+            stale_total, load_size
         );
 
         for (idx, batch) in stale_batches.into_iter().enumerate() {
@@ -70,7 +107,7 @@ pub async fn calculate_match_score(app_state: &AppState) {
                 batch.len()
             );
 
-            recompute_stale_matches(&app_state, batch).await;
+            score_id_pairs(app_state, &progress, batch, "stale").await;
         }
     }
 
@@ -93,7 +130,11 @@ pub async fn calculate_match_score(app_state: &AppState) {
                 batch.len()
             );
 
-            process_new_jobs(&app_state, batch).await;
+            if use_candidates {
+                process_job_candidates(app_state, &progress, batch).await;
+            } else {
+                process_new_jobs(app_state, &progress, batch).await;
+            }
         }
     }
 
@@ -116,13 +157,22 @@ pub async fn calculate_match_score(app_state: &AppState) {
                 batch.len()
             );
 
-            process_new_profiles(&app_state, batch).await;
+            if use_candidates {
+                process_profile_candidates(app_state, &progress, batch).await;
+            } else {
+                process_new_profiles(app_state, &progress, batch).await;
+            }
         }
     }
 
     /* ---------------- missing pair reconciliation ---------------- */
 
-    reconcile_missing_matches(&app_state, batch_size).await;
+    // Candidate mode deliberately leaves most pairs unscored.
+    if !use_candidates {
+        reconcile_missing_matches(app_state, &progress, load_size).await;
+    }
+
+    progress.finish(app_state).await;
 
     let elapsed = start.elapsed();
 
@@ -132,38 +182,82 @@ pub async fn calculate_match_score(app_state: &AppState) {
     );
 }
 
-pub async fn recompute_stale_matches(app_state: &AppState, stale_matches: Vec<StaleMatchRow>) {
-    for pair in stale_matches {
-        let job = match fetch_job_by_id(&app_state.db_pool, pair.job_id).await {
-            Ok(j) => j,
-            Err(e) => {
-                tracing::error!("failed to fetch job {}: {:?}", pair.job_id, e);
-                continue;
-            }
-        };
-
-        let profile = match fetch_profile_by_id(&app_state.db_pool, pair.profile_id).await {
-            Ok(p) => p,
-            Err(e) => {
-                tracing::error!("failed to fetch profile {}: {:?}", pair.profile_id, e);
-                continue;
-            }
-        };
+/// Loads the rows behind `(job_id, profile_id)` pairs and scores them. Pairs
+/// whose job or profile no longer exists count as failed.
+async fn score_id_pairs(
+    app_state: &Arc<AppState>,
+    progress: &Arc<MatchProgress>,
+    id_pairs: Vec<(Uuid, Uuid)>,
+    source: &'static str,
+) {
+    let job_ids: Vec<Uuid> = id_pairs
+        .iter()
+        .map(|(job_id, _)| *job_id)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let profile_ids: Vec<Uuid> = id_pairs
+        .iter()
+        .map(|(_, profile_id)| *profile_id)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+
+    let jobs = match fetch_jobs_by_ids(&app_state.db_pool, &job_ids).await {
+        Ok(j) => j,
+        Err(e) => {
+            error!("failed to fetch jobs: {:?}", e);
+            progress
+                .advance(app_state, id_pairs.len(), id_pairs.len())
+                .await;
+            return;
+        }
+    };
+    let profiles = match fetch_profiles_by_ids(&app_state.db_pool, &profile_ids).await {
+        Ok(p) => p,
+        Err(e) => {
+            error!("failed to fetch profiles: {:?}", e);
+            progress
+                .advance(app_state, id_pairs.len(), id_pairs.len())
+                .await;
+            return;
+        }
+    };
 
-        compute_and_upsert(app_state, &job, &profile, "stale").await;
-        info!(
-            "✅ finished stale match job={} profile={}",
-            job.id, profile.id
-        );
+    let job_index: HashMap<Uuid, usize> = jobs.iter().enumerate().map(|(i, j)| (j.id, i)).collect();
+    let profile_index: HashMap<Uuid, usize> = profiles
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (p.id, i))
+        .collect();
+
+    let requested = id_pairs.len();
+    let pairs: Vec<(usize, usize)> = id_pairs
+        .into_iter()
+        .filter_map(|(job_id, profile_id)| {
+            Some((*job_index.get(&job_id)?, *profile_index.get(&profile_id)?))
+        })
+        .collect();
+
+    let vanished = requested - pairs.len();
+    if vanished > 0 {
+        error!("{} {} pairs reference deleted rows", vanished, source);
+        progress.advance(app_state, vanished, vanished).await;
     }
+
+    score_pairs(app_state, progress, jobs, profiles, pairs, source).await;
 }
 
-pub async fn process_new_jobs(app_state: &AppState, new_jobs: Vec<JobLiteRow>) {
+pub async fn process_new_jobs(
+    app_state: &Arc<AppState>,
+    progress: &Arc<MatchProgress>,
+    new_jobs: Vec<JobLiteRow>,
+) {
     if new_jobs.is_empty() {
         return;
     }
 
-    let profiles = match fetch_all_profiles(&app_state.db_pool).await {
+    let mut profiles = match fetch_all_profiles(&app_state.db_pool).await {
         Ok(p) => p,
         Err(e) => {
             tracing::error!("failed to fetch profiles: {:?}", e);
@@ -185,24 +279,30 @@ pub async fn process_new_jobs(app_state: &AppState, new_jobs: Vec<JobLiteRow>) {
         profiles.len()
     );
 
-    for lite_job in new_jobs {
-        let job = match fetch_job_by_id(&app_state.db_pool, lite_job.id).await {
-            Ok(j) => j,
-            Err(e) => {
-                tracing::error!("failed to fetch job {}: {:?}", lite_job.id, e);
-                continue;
-            }
-        };
+    let embedded = ensure_profile_embeddings(app_state, &mut profiles).await;
+    if embedded > 0 {
+        info!("🧮 embedded {} profiles before scoring", embedded);
+    }
 
-        for profile in &profiles {
-            compute_and_upsert(app_state, &job, profile, "new_job").await;
+    let job_ids: Vec<Uuid> = new_jobs.iter().map(|j| j.id).collect();
+    let jobs = match fetch_jobs_by_ids(&app_state.db_pool, &job_ids).await {
+        Ok(j) => j,
+        Err(e) => {
+            tracing::error!("failed to fetch jobs: {:?}", e);
+            return;
         }
+    };
 
-        info!("✅ finished job {}", job.id);
-    }
+    let pairs = all_pairs(jobs.len(), profiles.len());
+    progress.add_total(app_state, "new_jobs", pairs.len()).await;
+    score_pairs(app_state, progress, jobs, profiles, pairs, "new_job").await;
 }
 
-pub async fn process_new_profiles(app_state: &AppState, new_profiles: Vec<ProfileLiteRow>) {
+pub async fn process_new_profiles(
+    app_state: &Arc<AppState>,
+    progress: &Arc<MatchProgress>,
+    new_profiles: Vec<ProfileLiteRow>,
+) {
     if new_profiles.is_empty() {
         return;
     }
@@ -228,53 +328,162 @@ pub async fn process_new_profiles(app_state: &AppState, new_profiles: Vec<Profil
         jobs.len()
     );
 
-    for lite_profile in new_profiles {
-        let profile = match fetch_profile_by_id(&app_state.db_pool, lite_profile.id).await {
-            Ok(p) => p,
-            Err(e) => {
-                error!("failed to fetch profile {}: {:?}", lite_profile.id, e);
-                continue;
-            }
+    let profile_ids: Vec<Uuid> = new_profiles.iter().map(|p| p.id).collect();
+    let mut profiles = match fetch_profiles_by_ids(&app_state.db_pool, &profile_ids).await {
+        Ok(p) => p,
+        Err(e) => {
+            error!("failed to fetch profiles: {:?}", e);
+            return;
+        }
+    };
+
+    ensure_profile_embeddings(app_state, &mut profiles).await;
+
+    let pairs = all_pairs(jobs.len(), profiles.len());
+    progress
+        .add_total(app_state, "new_profiles", pairs.len())
+        .await;
+    score_pairs(app_state, progress, jobs, profiles, pairs, "new_profile").await;
+}
+
+fn all_pairs(jobs: usize, profiles: usize) -> Vec<(usize, usize)> {
+    (0..jobs)
+        .flat_map(|j| (0..profiles).map(move |p| (j, p)))
+        .collect()
+}
+
+/// Scores `(job index, profile index)` pairs on a bounded worker pool. Each
+/// worker takes `batch` pairs, scores them and writes them in one upsert.
+/// Returns the pairs that weren't scored or written.
+pub(crate) async fn score_pairs(
+    app_state: &Arc<AppState>,
+    progress: &Arc<MatchProgress>,
+    jobs: Vec<JobRow>,
+    profiles: Vec<ProfileRow>,
+    pairs: Vec<(usize, usize)>,
+    source: &'static str,
+) -> HashSet<(usize, usize)> {
+    let mut failed = HashSet::new();
+    if pairs.is_empty() {
+        return failed;
+    }
+
+    let schedule = &app_state.config.cron.compute_match_scores;
+    let semaphore = Arc::new(Semaphore::new(schedule.concurrency.max(1)));
+    let jobs = Arc::new(jobs);
+    let profiles = Arc::new(profiles);
+    let mut workers = JoinSet::new();
+
+    // Kept so a worker that dies can be counted as failing its whole chunk.
+    let mut chunks: HashMap<tokio::task::Id, Vec<(usize, usize)>> = HashMap::new();
+
+    for chunk in chunk_vec(pairs, schedule.batch.max(1)) {
+        let Ok(permit) = semaphore.clone().acquire_owned().await else {
+            break;
         };
 
-        for job in &jobs {
-            compute_and_upsert(app_state, job, &profile, "new_profile").await;
-        }
+        let app_state = app_state.clone();
+        let progress = progress.clone();
+        let jobs = jobs.clone();
+        let profiles = profiles.clone();
+
+        let task_chunk = chunk.clone();
+        let handle = workers.spawn(async move {
+            let _permit = permit;
+            score_chunk(&app_state, &progress, &jobs, &profiles, task_chunk, source).await
+        });
+        chunks.insert(handle.id(), chunk);
+    }
 
-        info!("✅ finished profile {}", profile.id);
+    while let Some(result) = workers.join_next_with_id().await {
+        match result {
+            Ok((_, chunk_failed)) => failed.extend(chunk_failed),
+            Err(e) => {
+                error!(source = source, error = ?e, "match-score worker failed");
+                if let Some(chunk) = chunks.remove(&e.id()) {
+                    failed.extend(chunk);
+                }
+            }
+        }
     }
+
+    failed
 }
 
-async fn compute_and_upsert(
+async fn score_chunk(
     app_state: &AppState,
-    job: &JobRow,
-    profile: &ProfileRow,
+    progress: &MatchProgress,
+    jobs: &[JobRow],
+    profiles: &[ProfileRow],
+    chunk: Vec<(usize, usize)>,
     source: &'static str,
-) {
-    let (score, breakdown) = compute_match_score(app_state, job, profile).await;
-
-    if let Err(e) = upsert_match_score(
-        &app_state.db_pool,
-        job.id,
-        profile.id,
-        &job.hash,
-        &profile.hash,
-        score,
-        breakdown,
-    )
-    .await
-    {
+) -> Vec<(usize, usize)> {
+    let schedule = &app_state.config.cron.compute_match_scores;
+    let apply_floor = schedule.candidate_top_n > 0;
+
+    let mut upserts: Vec<MatchScoreUpsert> = Vec::with_capacity(chunk.len());
+    let mut upserted: Vec<(usize, usize)> = Vec::with_capacity(chunk.len());
+    let mut below_floor: Vec<(Uuid, Uuid)> = Vec::new();
+    let mut dropped: Vec<(usize, usize)> = Vec::new();
+    let mut failed: Vec<(usize, usize)> = Vec::new();
+
+    for &(job_idx, profile_idx) in &chunk {
+        let job = &jobs[job_idx];
+        let profile = &profiles[profile_idx];
+
+        // Unscored pairs keep whatever was stored and are retried next run.
+        let Some((score, breakdown)) = compute_match_score(app_state, job, profile).await else {
+            failed.push((job_idx, profile_idx));
+            continue;
+        };
+
+        // Below the floor: drop any earlier score for the pair instead of storing it.
+        if apply_floor && score < schedule.min_score {
+            below_floor.push((job.id, profile.id));
+            dropped.push((job_idx, profile_idx));
+            continue;
+        }
+
+        upserted.push((job_idx, profile_idx));
+        upserts.push(MatchScoreUpsert {
+            job_id: job.id,
+            profile_id: profile.id,
+            job_hash: job.hash.clone(),
+            profile_hash: profile.hash.clone(),
+            match_score: score,
+            score_breakdown: breakdown,
+        });
+    }
+
+    if let Err(e) = batch_upsert_match_scores(&app_state.db_pool, &upserts).await {
         error!(
             source = source,
-            job_id = %job.id,
-            profile_id = %profile.id,
+            pairs = upserts.len(),
             error = ?e,
-            "failed to upsert match score"
+            "failed to upsert match scores"
         );
+        failed.extend(upserted);
     }
+
+    if let Err(e) = delete_match_scores(&app_state.db_pool, &below_floor).await {
+        error!(
+            source = source,
+            pairs = below_floor.len(),
+            error = ?e,
+            "failed to delete match scores below floor"
+        );
+        failed.extend(dropped);
+    }
+
+    progress.advance(app_state, chunk.len(), failed.len()).await;
+    failed
 }
 
-pub async fn reconcile_missing_matches(app_state: &AppState, batch_size: usize) {
+pub async fn reconcile_missing_matches(
+    app_state: &Arc<AppState>,
+    progress: &Arc<MatchProgress>,
+    batch_size: usize,
+) {
     let missing = match fetch_missing_matches(&app_state.db_pool).await {
         Ok(v) => v,
         Err(e) => {
@@ -288,6 +497,14 @@ pub async fn reconcile_missing_matches(app_state: &AppState, batch_size: usize)
         return;
     }
 
+    progress
+        .add_total(app_state, "reconcile", missing.len())
+        .await;
+
+    let missing: Vec<(Uuid, Uuid)> = missing
+        .into_iter()
+        .map(|m| (m.job_id, m.profile_id))
+        .collect();
     let batches = chunk_vec(missing, batch_size);
     let total = batches.len();
 
@@ -306,25 +523,7 @@ pub async fn reconcile_missing_matches(app_state: &AppState, batch_size: usize)
             batch.len()
         );
 
-        for pair in batch {
-            let job = match fetch_job_by_id(&app_state.db_pool, pair.job_id).await {
-                Ok(j) => j,
-                Err(e) => {
-                    error!("failed to fetch job {}: {:?}", pair.job_id, e);
-                    continue;
-                }
-            };
-
-            let profile = match fetch_profile_by_id(&app_state.db_pool, pair.profile_id).await {
-                Ok(p) => p,
-                Err(e) => {
-                    error!("failed to fetch profile {}: {:?}", pair.profile_id, e);
-                    continue;
-                }
-            };
-
-            compute_and_upsert(app_state, &job, &profile, "reconcile").await;
-        }
+        score_id_pairs(app_state, progress, batch, "reconcile").await;
     }
 
     info!("🧩 match-score reconciliation completed");
@@ -0,0 +1,21 @@
+use crate::state::AppState;
+use redis::AsyncCommands;
+use std::collections::HashMap;
+
+pub const WEBHOOK_DUPLICATES_KEY: &str = "metrics:webhook:duplicates";
+
+/// Counters live in Redis so the numbers add up across replicas.
+pub async fn record_webhook_duplicate(app_state: &AppState, action: &str) -> anyhow::Result<()> {
+    let mut conn = app_state.redis_pool.get().await?;
+    conn.hincr::<_, _, _, ()>(WEBHOOK_DUPLICATES_KEY, action, 1)
+        .await?;
+    Ok(())
+}
+
+pub async fn get_webhook_duplicate_counts(
+    app_state: &AppState,
+) -> anyhow::Result<HashMap<String, u64>> {
+    let mut conn = app_state.redis_pool.get().await?;
+    let counts: HashMap<String, u64> = conn.hgetall(WEBHOOK_DUPLICATES_KEY).await?;
+    Ok(counts)
+}
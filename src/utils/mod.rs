@@ -1,4 +1,7 @@
 pub mod batching;
+pub mod beckn_context;
+pub mod beckn_log;
+pub mod correlation;
 pub mod cron;
 pub mod empeding;
 pub mod external_apis;
@@ -6,9 +9,17 @@ pub mod hash;
 pub mod http_client;
 pub mod job;
 pub mod logging;
+pub mod match_candidates;
+pub mod match_modes;
+pub mod match_progress;
+pub mod match_rules;
 pub mod match_score;
+pub mod metrics;
 pub mod notification;
 pub mod profiles;
+pub mod redact;
 pub mod redis;
+pub mod registry;
 pub mod search;
 pub mod shared;
+pub mod signing;
@@ -1,7 +1,10 @@
 use crate::config::AppConfig;
+use crate::db::profiles::fetch_profiles_pending_embedding;
 use crate::models::search::Pagination;
+use crate::services::match_score::ensure_profile_embeddings;
+use crate::state::AppState;
 use serde_json::{json, Value};
-use tracing::info;
+use tracing::{error, info};
 
 pub fn build_profiles_catalog(
     items: Vec<Value>,
@@ -48,3 +51,48 @@ pub fn extract_pagination(message: &Value) -> Pagination {
             .map(|v| v as u32),
     }
 }
+
+/// Embeds every profile whose stored vector is missing (new or changed hash)
+/// or came from another model or older embed rules. Returns how many profiles were embedded.
+pub async fn update_profile_embeddings(app_state: &AppState) -> usize {
+    let model = app_state.embedding.model();
+    let dimension = app_state.embedding.dimension() as i32;
+    let rules_hash = app_state.match_rules.current().embedding_inputs_hash();
+    let options = app_state.embedding.batch_options();
+    let page_size = (options.batch_size * options.concurrency).max(1) as i64;
+
+    let mut cursor = None;
+    let mut total = 0;
+
+    loop {
+        let mut profiles = match fetch_profiles_pending_embedding(
+            &app_state.db_pool,
+            model,
+            dimension,
+            &rules_hash,
+            cursor,
+            page_size,
+        )
+        .await
+        {
+            Ok(p) => p,
+            Err(e) => {
+                error!("❌ Failed to fetch profiles pending embedding: {:?}", e);
+                break;
+            }
+        };
+
+        let Some(last) = profiles.last() else {
+            break;
+        };
+        cursor = Some(last.id);
+
+        total += ensure_profile_embeddings(app_state, &mut profiles).await;
+    }
+
+    if total > 0 {
+        info!("🧮 Embedded {} profiles with {}", total, model);
+    }
+
+    total
+}
@@ -0,0 +1,83 @@
+use serde_json::Value;
+
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Keys that identify a person wherever they appear. A bare `name` is left
+/// out since descriptors and cities use it too; see `PII_CONTAINERS`.
+const PII_KEYS: &[&str] = &[
+    "phone",
+    "phoneNumber",
+    "mobile",
+    "mobileNumber",
+    "email",
+    "fullName",
+    "full_name",
+    "firstName",
+    "first_name",
+    "lastName",
+    "last_name",
+    "fatherName",
+    "father_name",
+    "dob",
+    "dateOfBirth",
+    "date_of_birth",
+    "address",
+    "gps",
+    "aadhaar",
+    "aadhaarNumber",
+    "aadhaar_number",
+    "aadhar",
+    "pan",
+    "panNumber",
+    "pan_number",
+    "idNumber",
+    "id_number",
+];
+
+/// Objects describing the candidate; everything in them except ids is PII.
+/// `profile` is the seeker metadata under a profile catalog item's tags.
+const PII_CONTAINERS: &[&str] = &[
+    "person", "customer", "contact", "whoIAm", "billing", "profile",
+];
+
+/// Returns a copy of a Beckn payload that is safe to persist for auditing.
+pub fn redact_pii(value: &Value) -> Value {
+    redact(value, false)
+}
+
+fn redact(value: &Value, inside_container: bool) -> Value {
+    match value {
+        Value::Object(map) => {
+            // A profile item's descriptor carries the candidate's name.
+            let is_profile_item = map
+                .get("tags")
+                .is_some_and(|tags| tags.get("profile").is_some());
+
+            Value::Object(
+                map.iter()
+                    .map(|(key, v)| {
+                        let redacted = if PII_KEYS.contains(&key.as_str())
+                            || (is_profile_item && key == "descriptor")
+                        {
+                            redact(v, true)
+                        } else if inside_container && key == "id" {
+                            v.clone()
+                        } else {
+                            redact(
+                                v,
+                                inside_container || PII_CONTAINERS.contains(&key.as_str()),
+                            )
+                        };
+                        (key.clone(), redacted)
+                    })
+                    .collect(),
+            )
+        }
+        Value::Array(items) => {
+            Value::Array(items.iter().map(|v| redact(v, inside_container)).collect())
+        }
+        Value::Null => Value::Null,
+        _ if inside_container => Value::String(REDACTED.to_string()),
+        other => other.clone(),
+    }
+}
@@ -0,0 +1,119 @@
+use crate::config::RegistryMode;
+use crate::state::AppState;
+use crate::utils::http_client::post_json_for_value;
+use anyhow::{anyhow, Result};
+use redis::AsyncCommands;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::fs;
+use tracing::{error, info};
+
+#[derive(Debug, Deserialize)]
+pub struct RegistryEntry {
+    pub subscriber_id: String,
+    #[serde(alias = "ukId")]
+    pub unique_key_id: String,
+    pub signing_public_key: String,
+}
+
+fn find_entry(
+    entries: Vec<RegistryEntry>,
+    subscriber_id: &str,
+    unique_key_id: &str,
+) -> Option<String> {
+    entries
+        .into_iter()
+        .find(|e| e.subscriber_id == subscriber_id && e.unique_key_id == unique_key_id)
+        .map(|e| e.signing_public_key)
+}
+
+async fn lookup_from_file(
+    path: &str,
+    subscriber_id: &str,
+    unique_key_id: &str,
+) -> Result<Option<String>> {
+    let data = fs::read_to_string(path).await?;
+    let entries: Vec<RegistryEntry> = serde_json::from_str(&data)?;
+    Ok(find_entry(entries, subscriber_id, unique_key_id))
+}
+
+async fn lookup_from_registry(
+    url: &str,
+    subscriber_id: &str,
+    unique_key_id: &str,
+) -> Result<Option<String>> {
+    let lookup_url = format!("{}/lookup", url.trim_end_matches('/'));
+    let response = post_json_for_value(
+        &lookup_url,
+        json!({
+            "subscriber_id": subscriber_id,
+            "ukId": unique_key_id
+        }),
+    )
+    .await?;
+
+    let entries: Vec<RegistryEntry> = match response {
+        Value::Array(items) => items
+            .into_iter()
+            .filter_map(|item| serde_json::from_value(item).ok())
+            .collect(),
+        _ => return Err(anyhow!("Unexpected registry lookup response")),
+    };
+
+    Ok(find_entry(entries, subscriber_id, unique_key_id))
+}
+
+/// Resolves a subscriber's signing public key, caching hits in Redis.
+pub async fn lookup_signing_public_key(
+    app_state: &AppState,
+    subscriber_id: &str,
+    unique_key_id: &str,
+) -> Result<String> {
+    let registry = &app_state.config.signing.registry;
+    let cache_key = format!("registry:{}:{}", subscriber_id, unique_key_id);
+
+    let mut conn = app_state.redis_pool.get().await?;
+
+    if let Ok(Some(cached)) = conn.get::<_, Option<String>>(&cache_key).await {
+        return Ok(cached);
+    }
+
+    let public_key = match registry.mode {
+        RegistryMode::File => {
+            let path = registry
+                .file_path
+                .as_deref()
+                .ok_or_else(|| anyhow!("signing.registry.file_path is not configured"))?;
+            lookup_from_file(path, subscriber_id, unique_key_id).await?
+        }
+        RegistryMode::Lookup => {
+            let url = registry
+                .url
+                .as_deref()
+                .ok_or_else(|| anyhow!("signing.registry.url is not configured"))?;
+            lookup_from_registry(url, subscriber_id, unique_key_id).await?
+        }
+    };
+
+    let public_key = public_key.ok_or_else(|| {
+        anyhow!(
+            "No registry entry for subscriber_id={} ukId={}",
+            subscriber_id,
+            unique_key_id
+        )
+    })?;
+
+    info!(
+        "🔑 Resolved signing key from registry (subscriber_id={}, ukId={})",
+        subscriber_id, unique_key_id
+    );
+
+    if let Err(e) = conn
+        .set_ex::<_, _, ()>(&cache_key, &public_key, registry.cache_ttl_secs)
+        .await
+    {
+        error!("❌ Failed to cache registry public key: {:?}", e);
+    }
+
+    Ok(public_key)
+}
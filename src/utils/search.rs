@@ -5,7 +5,7 @@ use crate::models::webhook::WebhookPayload;
 use crate::services::payload_generator::build_beckn_payload;
 use crate::state::AppState;
 use crate::utils::hash::hash_json;
-use crate::utils::http_client::post_json;
+use crate::utils::http_client::post_beckn_json;
 use chrono::Utc;
 use redis::AsyncCommands;
 use serde_json::{json, Value as JsonValue};
@@ -186,6 +186,10 @@ fn build_open_jobs_intent() -> Intent {
     }
 }
 
+/// Page size of the open-jobs crawl, and the cap on what other crons send
+/// to a single BPP per run.
+pub const OPEN_JOBS_PAGE_SIZE: u32 = 30;
+
 fn build_open_jobs_search_message(page: u32, limit: u32) -> SearchMessage {
     SearchMessage {
         intent: build_open_jobs_intent(),
@@ -255,7 +259,7 @@ pub async fn send_open_jobs_search(
     }
 
     let adapter_url = format!("{}/search", app_state.config.bap.caller_uri);
-    if let Err(e) = post_json(&adapter_url, payload, None).await {
+    if let Err(e) = post_beckn_json(app_state, &adapter_url, payload).await {
         error!(
             "❌ Failed to send open jobs search (txn_id={}, page={}): {}",
             txn_id, page, e
@@ -268,6 +272,42 @@ pub async fn send_open_jobs_search(
     }
 }
 
+/// Returns true if the BPPs may be searched for this query now, and starts the
+/// throttle window; false if a search went out within `cache.throttle_secs`.
+pub async fn claim_search_slot(app_state: &AppState, query_hash: &str) -> bool {
+    match app_state.redis_pool.get().await {
+        Ok(mut conn) => {
+            let last_call_key = format!("last_call:{}", query_hash);
+            match conn.exists::<_, bool>(&last_call_key).await {
+                Ok(exists) if exists => {
+                    let secs = app_state.config.cache.throttle_secs;
+                    info!(
+                        ": Skipping BAP call (already called within last {} {})",
+                        if secs % 60 == 0 { secs / 60 } else { secs },
+                        if secs % 60 == 0 { "min" } else { "secs" }
+                    );
+                    false
+                }
+                _ => {
+                    let _: () = conn
+                        .set_ex::<_, _, ()>(
+                            &last_call_key,
+                            "1",
+                            app_state.config.cache.throttle_secs,
+                        )
+                        .await
+                        .unwrap_or_default();
+                    true
+                }
+            }
+        }
+        Err(e) => {
+            error!("Failed to get Redis connection for throttle check: {:?}", e);
+            true
+        }
+    }
+}
+
 pub fn build_profile_json(req: &SearchTopKRequest) -> JsonValue {
     let mut metadata = serde_json::Map::new();
 
@@ -1,18 +1,13 @@
-use crate::config::AppConfig;
 use crate::models::webhook::{Ack, AckResponse, AckStatus};
-use crate::utils::http_client::post_json;
+use crate::state::AppState;
+use crate::utils::http_client::post_beckn_json;
 use anyhow::Result;
 use serde_json::Value;
-use std::sync::Arc;
 use tracing::info;
 
 use axum::Json;
 
-pub async fn send_to_bpp_caller(
-    action: &str,
-    payload: Value,
-    config: Arc<AppConfig>,
-) -> Result<()> {
+pub async fn send_to_bpp_caller(action: &str, payload: Value, app_state: &AppState) -> Result<()> {
     let txn_id = payload
         .get("context")
         .and_then(|ctx| ctx.get("transaction_id"))
@@ -28,9 +23,9 @@ pub async fn send_to_bpp_caller(
     );
     info!(target: "bpp", "──────────────────────────────────────────────");
 
-    let bpp_url = &config.bpp.caller_uri;
+    let bpp_url = &app_state.config.bpp.caller_uri;
     let full_url = format!("{}/{}", bpp_url.trim_end_matches('/'), full_action);
-    post_json(&full_url, payload, None).await
+    post_beckn_json(app_state, &full_url, payload).await
 }
 
 pub fn ack() -> Json<AckResponse> {
@@ -0,0 +1,230 @@
+use crate::config::AppConfig;
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use blake2::{Blake2b512, Digest};
+use chrono::Utc;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use std::collections::HashMap;
+
+pub const SIGNING_ALGORITHM: &str = "ed25519";
+pub const SIGNED_HEADERS: &str = "(created) (expires) digest";
+
+#[derive(Debug, Clone)]
+pub struct SignatureParams {
+    pub subscriber_id: String,
+    pub unique_key_id: String,
+    pub algorithm: String,
+    pub created: i64,
+    pub expires: i64,
+    pub signature: String,
+}
+
+pub fn body_digest(body: &[u8]) -> String {
+    let mut hasher = Blake2b512::new();
+    hasher.update(body);
+    BASE64.encode(hasher.finalize())
+}
+
+fn signing_string(created: i64, expires: i64, digest: &str) -> String {
+    format!(
+        "(created): {}\n(expires): {}\ndigest: BLAKE-512={}",
+        created, expires, digest
+    )
+}
+
+fn decode_signing_key(private_key: &str) -> Result<SigningKey> {
+    let bytes = BASE64
+        .decode(private_key.trim())
+        .map_err(|e| anyhow!("Invalid base64 signing private key: {}", e))?;
+
+    // Beckn registries hand out either the 32 byte seed or the 64 byte
+    // libsodium keypair (seed || public key).
+    let seed: [u8; 32] = match bytes.len() {
+        32 | 64 => bytes[..32].try_into()?,
+        n => {
+            return Err(anyhow!(
+                "Signing private key must be 32 or 64 bytes, got {}",
+                n
+            ))
+        }
+    };
+
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+fn decode_verifying_key(public_key: &str) -> Result<VerifyingKey> {
+    let bytes = BASE64
+        .decode(public_key.trim())
+        .map_err(|e| anyhow!("Invalid base64 signing public key: {}", e))?;
+
+    let key: [u8; 32] = bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| anyhow!("Signing public key must be 32 bytes, got {}", bytes.len()))?;
+
+    Ok(VerifyingKey::from_bytes(&key)?)
+}
+
+/// Builds the Beckn `Authorization` header value for an outgoing request body.
+pub fn build_authorization_header(config: &AppConfig, body: &[u8]) -> Result<String> {
+    let signing = &config.signing;
+    sign_body(
+        &config.bap.id,
+        &signing.unique_key_id,
+        &signing.private_key,
+        signing.expiry_secs,
+        body,
+    )
+}
+
+fn sign_body(
+    subscriber_id: &str,
+    unique_key_id: &str,
+    private_key: &str,
+    expiry_secs: i64,
+    body: &[u8],
+) -> Result<String> {
+    let key = decode_signing_key(private_key)?;
+
+    let created = Utc::now().timestamp();
+    let expires = created + expiry_secs;
+    let digest = body_digest(body);
+
+    let signature: Signature = key.sign(signing_string(created, expires, &digest).as_bytes());
+
+    Ok(format!(
+        "Signature keyId=\"{}|{}|{}\",algorithm=\"{}\",created=\"{}\",expires=\"{}\",headers=\"{}\",signature=\"{}\"",
+        subscriber_id,
+        unique_key_id,
+        SIGNING_ALGORITHM,
+        SIGNING_ALGORITHM,
+        created,
+        expires,
+        SIGNED_HEADERS,
+        BASE64.encode(signature.to_bytes())
+    ))
+}
+
+pub fn parse_authorization_header(header: &str) -> Result<SignatureParams> {
+    let params = header
+        .trim()
+        .strip_prefix("Signature ")
+        .ok_or_else(|| anyhow!("Authorization header is not a Signature header"))?;
+
+    let mut fields: HashMap<&str, &str> = HashMap::new();
+    for part in params.split(',') {
+        if let Some((key, value)) = part.trim().split_once('=') {
+            fields.insert(key.trim(), value.trim().trim_matches('"'));
+        }
+    }
+
+    let field = |name: &str| {
+        fields
+            .get(name)
+            .copied()
+            .ok_or_else(|| anyhow!("Missing '{}' in Authorization header", name))
+    };
+
+    let key_id: Vec<&str> = field("keyId")?.split('|').collect();
+    if key_id.len() != 3 {
+        return Err(anyhow!(
+            "keyId must be formatted as subscriber_id|unique_key_id|algorithm"
+        ));
+    }
+
+    Ok(SignatureParams {
+        subscriber_id: key_id[0].to_string(),
+        unique_key_id: key_id[1].to_string(),
+        algorithm: field("algorithm")?.to_string(),
+        created: field("created")?.parse()?,
+        expires: field("expires")?.parse()?,
+        signature: field("signature")?.to_string(),
+    })
+}
+
+/// Checks the signature window, allowing `max_skew_secs` of clock drift either
+/// way, and verifies it against the sender's public key.
+pub fn verify_signature(
+    params: &SignatureParams,
+    body: &[u8],
+    public_key: &str,
+    max_skew_secs: i64,
+) -> Result<()> {
+    if params.algorithm != SIGNING_ALGORITHM {
+        return Err(anyhow!(
+            "Unsupported signing algorithm '{}'",
+            params.algorithm
+        ));
+    }
+
+    let now = Utc::now().timestamp();
+    if params.created > now + max_skew_secs {
+        return Err(anyhow!("Signature created timestamp is in the future"));
+    }
+    if params.expires < now - max_skew_secs {
+        return Err(anyhow!("Signature has expired"));
+    }
+
+    let key = decode_verifying_key(public_key)?;
+    let signature_bytes = BASE64
+        .decode(&params.signature)
+        .map_err(|e| anyhow!("Invalid base64 signature: {}", e))?;
+    let signature = Signature::from_slice(&signature_bytes)?;
+
+    let message = signing_string(params.created, params.expires, &body_digest(body));
+
+    key.verify(message.as_bytes(), &signature)
+        .map_err(|_| anyhow!("Signature verification failed"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEED: [u8; 32] = [7; 32];
+
+    fn keypair() -> (String, String) {
+        let key = SigningKey::from_bytes(&SEED);
+        (
+            BASE64.encode(SEED),
+            BASE64.encode(key.verifying_key().to_bytes()),
+        )
+    }
+
+    fn signed(body: &[u8]) -> (SignatureParams, String) {
+        let (private_key, public_key) = keypair();
+        let header = sign_body("bap.example.com", "key-1", &private_key, 300, body).unwrap();
+        (parse_authorization_header(&header).unwrap(), public_key)
+    }
+
+    #[test]
+    fn sign_then_verify_round_trips() {
+        let body = br#"{"context":{"action":"on_search"}}"#;
+        let (params, public_key) = signed(body);
+
+        assert_eq!(params.subscriber_id, "bap.example.com");
+        assert_eq!(params.unique_key_id, "key-1");
+        assert!(verify_signature(&params, body, &public_key, 0).is_ok());
+    }
+
+    #[test]
+    fn tampered_body_is_rejected() {
+        let (params, public_key) = signed(br#"{"amount":10}"#);
+
+        assert!(verify_signature(&params, br#"{"amount":99}"#, &public_key, 0).is_err());
+    }
+
+    #[test]
+    fn created_ahead_of_clock_passes_within_skew() {
+        let body = b"{}";
+        let (mut params, public_key) = signed(body);
+        params.created += 10;
+
+        // Moving `created` invalidates the signature, so only the window check differs.
+        let err = verify_signature(&params, body, &public_key, 0).unwrap_err();
+        assert!(err.to_string().contains("future"));
+
+        let err = verify_signature(&params, body, &public_key, 30).unwrap_err();
+        assert!(err.to_string().contains("verification failed"));
+    }
+}
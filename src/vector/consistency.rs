@@ -0,0 +1,122 @@
+use crate::db::job::{
+    fetch_indexable_job_ids, fetch_job_embeddings_by_ids, fetch_job_ids_with_dimension_mismatch,
+};
+use crate::state::AppState;
+use crate::vector::index_store::{read_metadata, save_faiss};
+use serde::Serialize;
+use serde_json::{json, Value as JsonValue};
+use std::collections::HashSet;
+use tracing::{error, info};
+use uuid::Uuid;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Differences between the FAISS id map and the jobs table.
+#[derive(Debug, Default, Serialize)]
+pub struct ConsistencyReport {
+    // Active jobs with a current vector that FAISS doesn't know about
+    pub missing: Vec<Uuid>,
+    // FAISS entries whose job is inactive, deleted or embedded by another model
+    pub orphaned: Vec<Uuid>,
+    // Active jobs whose stored vector length differs from the index dimension
+    pub dimension_mismatch: Vec<Uuid>,
+}
+
+impl ConsistencyReport {
+    pub fn is_consistent(&self) -> bool {
+        self.missing.is_empty() && self.orphaned.is_empty() && self.dimension_mismatch.is_empty()
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct RepairSummary {
+    pub added: usize,
+    pub removed: usize,
+    pub failed: usize,
+}
+
+pub async fn index_stats(app_state: &AppState) -> JsonValue {
+    let faiss = app_state.faiss.read().await.clone();
+    let index_path = faiss.vector.index_path.as_str();
+
+    let file_size = std::fs::metadata(index_path).ok().map(|m| m.len());
+    let saved = read_metadata(index_path);
+
+    json!({
+        "ntotal": faiss.ntotal().await,
+        "mapped_jobs": faiss.mapped_job_ids().await.len(),
+        "tombstones": faiss.state.lock().await.tombstones(),
+        "dimension": faiss.spec.dimension,
+        "index_type": faiss.vector.index_type,
+        "factory": faiss.spec.factory,
+        "metric": faiss.spec.metric,
+        "embedding_model": faiss.spec.embedding_model,
+        "trained": faiss.is_trained().await,
+        "rebuilding": faiss.is_rebuilding().await,
+        "index_path": index_path,
+        "file_size_bytes": file_size,
+        "last_saved_at": saved.map(|m| m.saved_at),
+    })
+}
+
+pub async fn check(app_state: &AppState) -> Result<ConsistencyReport, BoxError> {
+    let faiss = app_state.faiss.read().await.clone();
+    let dimension = faiss.spec.dimension as i32;
+
+    let indexable: HashSet<Uuid> =
+        fetch_indexable_job_ids(&app_state.db_pool, &faiss.spec.embedding_model, dimension)
+            .await?
+            .into_iter()
+            .collect();
+    let mismatched = fetch_job_ids_with_dimension_mismatch(&app_state.db_pool, dimension).await?;
+    let mapped = faiss.mapped_job_ids().await;
+
+    Ok(ConsistencyReport {
+        missing: indexable.difference(&mapped).copied().collect(),
+        orphaned: mapped.difference(&indexable).copied().collect(),
+        dimension_mismatch: mismatched,
+    })
+}
+
+/// Adds missing jobs and drops orphaned ones; everything else is left alone.
+pub async fn repair(app_state: &AppState) -> Result<RepairSummary, BoxError> {
+    let report = check(app_state).await?;
+    let mut summary = RepairSummary::default();
+
+    if report.missing.is_empty() && report.orphaned.is_empty() {
+        return Ok(summary);
+    }
+
+    let faiss = app_state.faiss.read().await.clone();
+
+    for job_id in &report.orphaned {
+        match faiss.remove(*job_id).await {
+            Ok(()) => summary.removed += 1,
+            Err(e) => {
+                error!("Failed to remove job_id={} from FAISS: {}", job_id, e);
+                summary.failed += 1;
+            }
+        }
+    }
+
+    for chunk in report.missing.chunks(500) {
+        for job in fetch_job_embeddings_by_ids(&app_state.db_pool, chunk).await? {
+            match faiss.upsert(job.id, job.embedding).await {
+                Ok(()) => summary.added += 1,
+                Err(e) => {
+                    error!("Failed to add job_id={} to FAISS: {}", job.id, e);
+                    summary.failed += 1;
+                }
+            }
+        }
+    }
+
+    save_faiss(&faiss).await?;
+
+    info!(
+        "🩹 FAISS repair: {} added, {} removed, {} failed",
+        summary.added, summary.removed, summary.failed
+    );
+
+    Ok(summary)
+}
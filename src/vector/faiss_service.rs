@@ -1,96 +1,277 @@
-use deadpool_redis::redis::cmd;
-use deadpool_redis::Pool;
+use crate::config::{VectorConfig, VectorIndexType, VectorMetric};
+use faiss::index::autotune::ParameterSpace;
 use faiss::index::IndexImpl;
 use faiss::selector::IdSelector;
 use faiss::{index_factory, Index, MetricType};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use tracing::{error, warn};
 use uuid::Uuid;
-#[derive(Clone)]
-pub struct FaissService {
-    pub index: Arc<Mutex<IndexImpl>>,
-    pub redis_pool: Pool,
+
+/// What an index was built as; persisted next to it so a restart can tell
+/// whether the file on disk still matches the configuration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexSpec {
+    pub factory: String,
+    pub metric: VectorMetric,
+    pub dimension: u32,
+    pub embedding_model: String,
 }
 
-impl FaissService {
-    pub fn new(dimension: u32, redis_pool: Pool) -> Self {
-        let index: IndexImpl = index_factory(dimension, "IDMap,Flat", MetricType::InnerProduct)
-            .expect("FAISS init failed");
+impl IndexSpec {
+    pub fn from_config(vector: &VectorConfig, dimension: u32, embedding_model: &str) -> Self {
+        let factory = match &vector.index_factory {
+            Some(factory) => factory.clone(),
+            None => match vector.index_type {
+                VectorIndexType::Flat => "IDMap,Flat".to_string(),
+                VectorIndexType::Hnsw => format!("IDMap,HNSW{},Flat", vector.hnsw_m),
+                VectorIndexType::IvfFlat => format!("IDMap,IVF{},Flat", vector.nlist),
+                VectorIndexType::IvfPq => format!(
+                    "IDMap,IVF{},PQ{}x{}",
+                    vector.nlist, vector.pq_m, vector.pq_nbits
+                ),
+            },
+        };
 
         Self {
-            index: Arc::new(Mutex::new(index)),
-            redis_pool,
+            factory,
+            metric: vector.metric.clone(),
+            dimension,
+            embedding_model: embedding_model.to_string(),
         }
     }
 
-    async fn get_or_create_faiss_id(
-        &self,
-        job_id: Uuid,
-    ) -> Result<i64, Box<dyn std::error::Error + Send + Sync>> {
-        let mut conn = self.redis_pool.get().await?;
-
-        let job_key = format!("faiss:job_to_id:{}", job_id);
-
-        let existing_id: Option<i64> = cmd("GET").arg(&job_key).query_async(&mut conn).await?;
+    /// HNSW graphs can't drop vectors, so replaced ones stay in the index
+    /// as tombstones until the next rebuild.
+    pub fn supports_removal(&self) -> bool {
+        !self.factory.to_uppercase().contains("HNSW")
+    }
 
-        if let Some(id) = existing_id {
-            return Ok(id);
+    fn metric_type(&self) -> MetricType {
+        match self.metric {
+            VectorMetric::InnerProduct => MetricType::InnerProduct,
+            VectorMetric::L2 => MetricType::L2,
         }
+    }
+}
 
-        let new_id: i64 = cmd("INCR")
-            .arg("faiss:next_id")
-            .query_async(&mut conn)
-            .await?;
+/// Bidirectional job id <-> FAISS label map, kept beside the index it describes.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct IdMap {
+    pub next_id: i64,
+    pub job_to_id: HashMap<Uuid, i64>,
+    #[serde(skip)]
+    pub id_to_job: HashMap<i64, Uuid>,
+}
+
+impl IdMap {
+    /// Rebuilds the reverse side after deserialization.
+    pub fn with_reverse(mut self) -> Self {
+        self.id_to_job = self.job_to_id.iter().map(|(j, i)| (*i, *j)).collect();
+        self
+    }
 
-        let reverse_key = format!("faiss:id_to_job:{}", new_id);
+    pub fn len(&self) -> usize {
+        self.job_to_id.len()
+    }
 
-        let _: () = cmd("SET")
-            .arg(&job_key)
-            .arg(new_id)
-            .query_async(&mut conn)
-            .await?;
+    pub fn is_empty(&self) -> bool {
+        self.job_to_id.is_empty()
+    }
 
-        let _: () = cmd("SET")
-            .arg(&reverse_key)
-            .arg(job_id.to_string())
-            .query_async(&mut conn)
-            .await?;
+    fn get_or_assign(&mut self, job_id: Uuid) -> i64 {
+        if let Some(id) = self.job_to_id.get(&job_id) {
+            return *id;
+        }
+        self.next_id += 1;
+        self.job_to_id.insert(job_id, self.next_id);
+        self.id_to_job.insert(self.next_id, job_id);
+        self.next_id
+    }
 
-        Ok(new_id)
+    /// Gives a job a fresh label, unmapping the old one so hits on it are dropped.
+    fn reassign(&mut self, job_id: Uuid) -> i64 {
+        self.remove(job_id);
+        self.get_or_assign(job_id)
     }
 
-    async fn get_job_id_from_faiss_id(
-        &self,
-        faiss_id: i64,
-    ) -> Result<Option<Uuid>, Box<dyn std::error::Error + Send + Sync>> {
-        let mut conn = self.redis_pool.get().await?;
+    fn remove(&mut self, job_id: Uuid) -> Option<i64> {
+        let id = self.job_to_id.remove(&job_id)?;
+        self.id_to_job.remove(&id);
+        Some(id)
+    }
+}
 
-        let reverse_key = format!("faiss:id_to_job:{}", faiss_id);
+/// A write made to the live index while a shadow rebuild is running.
+pub enum JournalOp {
+    Upsert(Uuid, Vec<f32>),
+    Remove(Uuid),
+}
 
-        let job_id_str: Option<String> =
-            cmd("GET").arg(&reverse_key).query_async(&mut conn).await?;
+/// The index and its id map, locked together so they can't drift apart.
+pub struct IndexState {
+    pub index: IndexImpl,
+    pub ids: IdMap,
+    // Some while a rebuild is running, so writes can be replayed onto the shadow
+    pub journal: Option<Vec<JournalOp>>,
+    // False for HNSW: replaced vectors are unmapped instead of removed
+    removable: bool,
+}
 
-        match job_id_str {
-            Some(s) => Ok(Some(Uuid::parse_str(&s)?)),
-            None => Ok(None),
+impl IndexState {
+    pub fn new(index: IndexImpl, ids: IdMap, removable: bool) -> Self {
+        Self {
+            index,
+            ids,
+            journal: None,
+            removable,
         }
     }
 
-    pub async fn add(
-        &self,
+    /// Vectors still in the index whose label no longer maps to a job.
+    pub fn tombstones(&self) -> usize {
+        (self.index.ntotal() as usize).saturating_sub(self.ids.len())
+    }
+
+    /// Adds or replaces a job's vector; `embedding` must already be normalised.
+    pub fn upsert(
+        &mut self,
         job_id: Uuid,
-        mut embedding: Vec<f32>,
+        embedding: &[f32],
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        normalize(&mut embedding);
+        if !self.index.is_trained() {
+            return Err("FAISS index is not trained yet".into());
+        }
+
+        let faiss_id = match self.ids.job_to_id.get(&job_id).copied() {
+            Some(existing) if self.removable => {
+                let selector = IdSelector::batch(&[existing.into()])?;
+                self.index.remove_ids(&selector)?;
+                existing
+            }
+            Some(_) => self.ids.reassign(job_id),
+            None => self.ids.get_or_assign(job_id),
+        };
+
+        self.index.add_with_ids(embedding, &[faiss_id.into()])?;
+
+        Ok(())
+    }
+
+    pub fn remove(&mut self, job_id: Uuid) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let Some(faiss_id) = self.ids.remove(job_id) else {
+            return Ok(());
+        };
+
+        if self.removable {
+            let selector = IdSelector::batch(&[faiss_id.into()])?;
+            self.index.remove_ids(&selector)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn apply(&mut self, op: JournalOp) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        match op {
+            JournalOp::Upsert(job_id, embedding) => self.upsert(job_id, &embedding),
+            JournalOp::Remove(job_id) => self.remove(job_id),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct FaissService {
+    pub state: Arc<Mutex<IndexState>>,
+    pub spec: IndexSpec,
+    pub vector: VectorConfig,
+    // Orders saves so an older snapshot never lands on disk after a newer one
+    pub save_lock: Arc<Mutex<()>>,
+}
+
+impl FaissService {
+    pub fn new(
+        vector: &VectorConfig,
+        spec: IndexSpec,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let index = build_index(vector, &spec)?;
+
+        Ok(Self::from_parts(vector, spec, index, IdMap::default()))
+    }
+
+    pub fn from_parts(
+        vector: &VectorConfig,
+        spec: IndexSpec,
+        index: IndexImpl,
+        ids: IdMap,
+    ) -> Self {
+        let removable = spec.supports_removal();
+        Self {
+            state: Arc::new(Mutex::new(IndexState::new(index, ids, removable))),
+            spec,
+            vector: vector.clone(),
+            save_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// A fresh, empty (and possibly untrained) index for this service's spec.
+    pub fn empty_index(&self) -> Result<IndexImpl, Box<dyn std::error::Error + Send + Sync>> {
+        build_index(&self.vector, &self.spec)
+    }
+
+    pub async fn ntotal(&self) -> u64 {
+        self.state.lock().await.index.ntotal()
+    }
+
+    pub async fn is_trained(&self) -> bool {
+        self.state.lock().await.index.is_trained()
+    }
 
-        let faiss_id = self.get_or_create_faiss_id(job_id).await?;
+    pub async fn mapped_job_ids(&self) -> HashSet<Uuid> {
+        self.state
+            .lock()
+            .await
+            .ids
+            .job_to_id
+            .keys()
+            .copied()
+            .collect()
+    }
 
-        let mut index = self.index.lock().await;
-        index.add_with_ids(&embedding, &[faiss_id.into()])?;
+    pub async fn is_rebuilding(&self) -> bool {
+        self.state.lock().await.journal.is_some()
+    }
 
+    /// Starts journaling writes for a shadow rebuild; errors if one is running.
+    pub async fn begin_rebuild(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut state = self.state.lock().await;
+        if state.journal.is_some() {
+            return Err("FAISS rebuild already in progress".into());
+        }
+        state.journal = Some(Vec::new());
         Ok(())
     }
 
+    /// Replays writes made during the rebuild onto `shadow`, then swaps it in.
+    /// Readers see either the old index or the complete new one.
+    pub async fn finish_rebuild(&self, mut shadow: IndexState) {
+        let mut state = self.state.lock().await;
+
+        for op in state.journal.take().unwrap_or_default() {
+            if let Err(e) = shadow.apply(op) {
+                error!("Failed to replay FAISS write onto rebuilt index: {}", e);
+            }
+        }
+
+        *state = shadow;
+    }
+
+    /// Drops the journal after a failed rebuild; the live index is untouched.
+    pub async fn abort_rebuild(&self) {
+        self.state.lock().await.journal = None;
+    }
+
     pub async fn search(
         &self,
         mut query: Vec<f32>,
@@ -98,54 +279,76 @@ impl FaissService {
     ) -> Result<Vec<(Uuid, f32)>, Box<dyn std::error::Error + Send + Sync>> {
         normalize(&mut query);
 
-        let mut index = self.index.lock().await;
-        let result = index.search(&query, k)?;
-        drop(index);
+        let mut state = self.state.lock().await;
+        self.search_locked(&mut state, &query, k)
+    }
+
+    /// Top-k among `allowed` only. Over-fetches by `vector.overfetch` and
+    /// doubles the candidate count until k pass or the whole index was scanned.
+    pub async fn search_filtered(
+        &self,
+        mut query: Vec<f32>,
+        k: usize,
+        allowed: &HashSet<Uuid>,
+    ) -> Result<Vec<(Uuid, f32)>, Box<dyn std::error::Error + Send + Sync>> {
+        if k == 0 || allowed.is_empty() {
+            return Ok(Vec::new());
+        }
 
-        let mut output = Vec::new();
+        normalize(&mut query);
 
-        for (idx, score) in result.labels.iter().zip(result.distances.iter()) {
-            let raw_id = match idx.get() {
-                Some(v) => v as i64,
-                None => continue,
-            };
+        let mut state = self.state.lock().await;
+        let ntotal = state.index.ntotal() as usize;
+        let mut fetch = (k * self.vector.overfetch.max(1)).min(ntotal.max(1));
+
+        loop {
+            let mut output = self.search_locked(&mut state, &query, fetch)?;
+            output.retain(|(job_id, _)| allowed.contains(job_id));
 
-            if let Some(job_id) = self.get_job_id_from_faiss_id(raw_id).await? {
-                output.push((job_id, *score));
+            if output.len() >= k || fetch >= ntotal {
+                output.truncate(k);
+                return Ok(output);
             }
-        }
 
-        Ok(output)
+            fetch = (fetch * 2).min(ntotal);
+        }
     }
 
-    async fn get_faiss_id(
+    fn search_locked(
         &self,
-        job_id: Uuid,
-    ) -> Result<Option<i64>, Box<dyn std::error::Error + Send + Sync>> {
-        let mut conn = self.redis_pool.get().await?;
-        let job_key = format!("faiss:job_to_id:{}", job_id);
+        state: &mut IndexState,
+        query: &[f32],
+        k: usize,
+    ) -> Result<Vec<(Uuid, f32)>, Box<dyn std::error::Error + Send + Sync>> {
+        // Tombstoned hits are skipped below, so fetch enough to still fill k.
+        let ntotal = state.index.ntotal() as usize;
+        let fetch = (k + state.tombstones()).min(ntotal.max(k));
+        let result = state.index.search(query, fetch)?;
 
-        let faiss_id: Option<i64> = cmd("GET").arg(&job_key).query_async(&mut conn).await?;
-        Ok(faiss_id)
-    }
+        let mut output: Vec<(Uuid, f32)> = Vec::new();
 
-    async fn delete_faiss_mappings(
-        &self,
-        job_id: Uuid,
-        faiss_id: i64,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let mut conn = self.redis_pool.get().await?;
+        for (idx, score) in result.labels.iter().zip(result.distances.iter()) {
+            let raw_id = match idx.get() {
+                Some(v) => v as i64,
+                None => continue,
+            };
 
-        let job_key = format!("faiss:job_to_id:{}", job_id);
-        let reverse_key = format!("faiss:id_to_job:{}", faiss_id);
+            // Squared L2 between unit vectors maps back to cosine similarity.
+            let similarity = match self.spec.metric {
+                VectorMetric::InnerProduct => *score,
+                VectorMetric::L2 => 1.0 - *score / 2.0,
+            };
 
-        let _: () = cmd("DEL")
-            .arg(&job_key)
-            .arg(&reverse_key)
-            .query_async(&mut conn)
-            .await?;
+            if let Some(job_id) = state.ids.id_to_job.get(&raw_id) {
+                // Indexes saved before labels were reassigned may map a job twice.
+                if !output.iter().any(|(id, _)| id == job_id) {
+                    output.push((*job_id, similarity));
+                }
+            }
+        }
 
-        Ok(())
+        output.truncate(k);
+        Ok(output)
     }
 
     pub async fn upsert(
@@ -155,44 +358,68 @@ impl FaissService {
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         normalize(&mut embedding);
 
-        let faiss_id = match self.get_faiss_id(job_id).await? {
-            Some(id) => id,
-            None => self.get_or_create_faiss_id(job_id).await?,
-        };
-
-        let mut index = self.index.lock().await;
-
-        let selector = IdSelector::batch(&[faiss_id.into()])?;
-        let _ = index.remove_ids(&selector);
-
-        index.add_with_ids(&embedding, &[faiss_id.into()])?;
+        let mut state = self.state.lock().await;
+        if let Some(journal) = state.journal.as_mut() {
+            journal.push(JournalOp::Upsert(job_id, embedding.clone()));
+        }
 
-        Ok(())
+        state.upsert(job_id, &embedding)
     }
 
     pub async fn remove(
         &self,
         job_id: Uuid,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let faiss_id = match self.get_faiss_id(job_id).await? {
-            Some(id) => id,
-            None => return Ok(()),
-        };
+        let mut state = self.state.lock().await;
+        if let Some(journal) = state.journal.as_mut() {
+            journal.push(JournalOp::Remove(job_id));
+        }
 
-        let mut index = self.index.lock().await;
+        state.remove(job_id)
+    }
+}
 
-        let selector = IdSelector::batch(&[faiss_id.into()])?;
-        let _ = index.remove_ids(&selector);
+fn build_index(
+    vector: &VectorConfig,
+    spec: &IndexSpec,
+) -> Result<IndexImpl, Box<dyn std::error::Error + Send + Sync>> {
+    let index = index_factory(spec.dimension, &spec.factory, spec.metric_type())?;
+    tune_index(&index, vector);
+    Ok(index)
+}
 
-        drop(index);
+/// Applies query-time parameters (efSearch for HNSW, nprobe for IVF).
+pub fn tune_index(index: &IndexImpl, vector: &VectorConfig) {
+    let params: &[(&str, u32)] = match (&vector.index_factory, &vector.index_type) {
+        (Some(_), _) => &[("efSearch", vector.ef_search), ("nprobe", vector.nprobe)],
+        (None, VectorIndexType::Flat) => &[],
+        (None, VectorIndexType::Hnsw) => &[("efSearch", vector.ef_search)],
+        (None, VectorIndexType::IvfFlat | VectorIndexType::IvfPq) => &[("nprobe", vector.nprobe)],
+    };
+
+    if params.is_empty() {
+        return;
+    }
 
-        self.delete_faiss_mappings(job_id, faiss_id).await?;
+    let space = match ParameterSpace::new() {
+        Ok(space) => space,
+        Err(e) => {
+            warn!("⚠️ FAISS ParameterSpace unavailable: {}", e);
+            return;
+        }
+    };
 
-        Ok(())
+    for (name, value) in params {
+        // A raw factory string may not have every parameter; that's fine.
+        if let Err(e) = space.set_index_parameter(index, name, *value) {
+            if vector.index_factory.is_none() {
+                warn!("⚠️ Failed to set FAISS {}={}: {}", name, value, e);
+            }
+        }
     }
 }
 
-fn normalize(v: &mut [f32]) {
+pub fn normalize(v: &mut [f32]) {
     let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
     if norm > 0.0 {
         for x in v.iter_mut() {
@@ -1,86 +1,308 @@
+use crate::config::VectorConfig;
 use crate::db::job::stream_active_jobs_with_embeddings;
 use crate::state::AppState;
-use crate::utils::redis::delete_keys_by_pattern;
-use crate::vector::faiss_service::FaissService;
-use deadpool_redis::{redis::cmd, Pool};
+use crate::vector::faiss_service::{
+    normalize, tune_index, FaissService, IdMap, IndexSpec, IndexState,
+};
+use crate::workers::faiss_rebuild::{record_indexed, PROGRESS_EVERY};
+use chrono::{DateTime, Utc};
 use faiss::index::IndexImpl;
-use faiss::{index_factory, read_index, write_index, MetricType};
+use faiss::index::TryClone;
+use faiss::{read_index, write_index, Index};
 use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::sync::Arc;
-use tokio::sync::Mutex;
 use tracing::{error, info};
 
-const INDEX_FILE: &str = "faiss.index";
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IndexMetadata {
+    #[serde(flatten)]
+    pub spec: IndexSpec,
+    pub trained: bool,
+    pub ntotal: u64,
+    pub id_count: usize,
+    pub saved_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PersistedIds {
+    saved_at: DateTime<Utc>,
+    #[serde(flatten)]
+    ids: IdMap,
+}
+
+fn metadata_path(index_path: &str) -> String {
+    format!("{}.meta.json", index_path)
+}
+
+fn ids_path(index_path: &str) -> String {
+    format!("{}.ids.json", index_path)
+}
+
+fn tmp_path(path: &str) -> String {
+    format!("{}.tmp", path)
+}
+
+/// Metadata of the last save, if there is one on disk.
+pub fn read_metadata(index_path: &str) -> Option<IndexMetadata> {
+    let bytes = std::fs::read(metadata_path(index_path)).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
 
+/// Writes index, id map and metadata to temp files, then renames them into
+/// place. Metadata goes last and carries the generation both others must match.
+///
+/// Only the in-memory snapshot is taken under the index lock; the file I/O
+/// runs after it is released so searches and upserts aren't blocked on disk.
 pub async fn save_faiss(
     service: &FaissService,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let index = service.index.lock().await;
-    write_index(&*index, INDEX_FILE)?;
-    Ok(())
+    let _save_guard = service.save_lock.lock().await;
+
+    let index_path = service.vector.index_path.clone();
+    let saved_at = Utc::now();
+
+    let (index, ids, metadata) = {
+        let state = service.state.lock().await;
+
+        let ids = serde_json::to_vec(&PersistedIds {
+            saved_at,
+            ids: state.ids.clone(),
+        })?;
+        let metadata = IndexMetadata {
+            spec: service.spec.clone(),
+            trained: state.index.is_trained(),
+            ntotal: state.index.ntotal(),
+            id_count: state.ids.len(),
+            saved_at,
+        };
+
+        (state.index.try_clone()?, ids, metadata)
+    };
+
+    tokio::task::spawn_blocking(
+        move || -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            let meta_path = metadata_path(&index_path);
+            let ids_file = ids_path(&index_path);
+
+            write_index(&index, tmp_path(&index_path))?;
+            std::fs::write(tmp_path(&ids_file), ids)?;
+            std::fs::write(tmp_path(&meta_path), serde_json::to_vec_pretty(&metadata)?)?;
+
+            std::fs::rename(tmp_path(&index_path), &index_path)?;
+            std::fs::rename(tmp_path(&ids_file), &ids_file)?;
+            std::fs::rename(tmp_path(&meta_path), &meta_path)?;
+
+            Ok(())
+        },
+    )
+    .await?
 }
 
+/// Loads the saved index if its metadata matches `spec` and the id map agrees
+/// with it; otherwise errors so the caller starts empty and a rebuild picks it up.
 pub fn load_faiss(
-    dimension: u32,
-    redis_pool: Pool,
+    vector: &VectorConfig,
+    spec: IndexSpec,
 ) -> Result<FaissService, Box<dyn std::error::Error + Send + Sync>> {
-    let index: IndexImpl = if Path::new(INDEX_FILE).exists() {
-        read_index(INDEX_FILE)?
+    let index_path = vector.index_path.as_str();
+    if !Path::new(index_path).exists() {
+        return FaissService::new(vector, spec);
+    }
+
+    let meta_path = metadata_path(index_path);
+    let metadata: IndexMetadata = match std::fs::read(&meta_path) {
+        Ok(bytes) => serde_json::from_slice(&bytes)?,
+        Err(_) => return Err(format!("{} is missing", meta_path).into()),
+    };
+
+    if metadata.spec != spec {
+        return Err(format!(
+            "FAISS index was built as {:?}, config wants {:?}",
+            metadata.spec, spec
+        )
+        .into());
+    }
+
+    let ids_file = ids_path(index_path);
+    let persisted: PersistedIds = match std::fs::read(&ids_file) {
+        Ok(bytes) => serde_json::from_slice(&bytes)?,
+        Err(_) => return Err(format!("{} is missing", ids_file).into()),
+    };
+    let ids = persisted.ids.with_reverse();
+
+    let index = read_index(index_path)?;
+    if index.d() != spec.dimension {
+        return Err(format!(
+            "FAISS index has dimension {}, embedding.dimension is {}",
+            index.d(),
+            spec.dimension
+        )
+        .into());
+    }
+
+    // HNSW keeps replaced vectors around, so it may hold more than it maps.
+    let counts_agree = if !spec.supports_removal() {
+        index.ntotal() >= ids.len() as u64
     } else {
-        index_factory(dimension, "IDMap,Flat", MetricType::InnerProduct)?
+        index.ntotal() == ids.len() as u64
     };
 
-    Ok(FaissService {
-        index: Arc::new(Mutex::new(index)),
-        redis_pool,
-    })
+    if persisted.saved_at != metadata.saved_at
+        || metadata.ntotal != index.ntotal()
+        || metadata.id_count != ids.len()
+        || !counts_agree
+    {
+        return Err(format!(
+            "FAISS index ({} vectors) and id map ({} ids) are out of sync",
+            index.ntotal(),
+            ids.len()
+        )
+        .into());
+    }
+
+    tune_index(&index, vector);
+
+    info!(
+        "✅ Loaded FAISS index from {} ({} vectors)",
+        index_path,
+        index.ntotal()
+    );
+
+    Ok(FaissService::from_parts(vector, spec, index, ids))
+}
+
+/// Reservoir-samples up to `limit` active vectors for IVF/PQ training.
+async fn sample_training_vectors(
+    app_state: &Arc<AppState>,
+    dimension: u32,
+    limit: usize,
+) -> Result<Vec<f32>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut rows = stream_active_jobs_with_embeddings(
+        &app_state.db_pool,
+        app_state.embedding.model(),
+        dimension as i32,
+    );
+
+    let mut sample: Vec<Vec<f32>> = Vec::with_capacity(limit.min(10_000));
+    let mut seen: usize = 0;
+
+    while let Some(job) = rows.try_next().await? {
+        seen += 1;
+        let mut embedding = job.embedding;
+        normalize(&mut embedding);
+
+        if sample.len() < limit {
+            sample.push(embedding);
+        } else {
+            let slot = rand::random_range(0..seen);
+            if slot < limit {
+                sample[slot] = embedding;
+            }
+        }
+    }
+
+    Ok(sample.into_iter().flatten().collect())
 }
 
+/// Builds a fresh index and id map off to the side and swaps them in at the
+/// end; the live index keeps serving searches for the whole rebuild. Writes
+/// made meanwhile are journaled and replayed onto the new index before the swap.
 pub async fn rebuild_faiss_from_db(
     app_state: &Arc<AppState>,
     dimension: u32,
+    job_id: Option<&str>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    info!("Starting full FAISS rebuild from DB");
+    let faiss = app_state.faiss.read().await.clone();
+    faiss.begin_rebuild().await?;
 
-    {
-        let mut conn = app_state.redis_pool.get().await?;
+    rebuild_claimed(app_state, &faiss, dimension, job_id).await
+}
 
-        delete_keys_by_pattern(&mut conn, "faiss:*").await?;
+/// Runs a rebuild on `faiss` whose rebuild flag the caller already claimed
+/// with `begin_rebuild`; the claim is released on success and failure alike.
+pub async fn rebuild_claimed(
+    app_state: &Arc<AppState>,
+    faiss: &FaissService,
+    dimension: u32,
+    job_id: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    info!("Starting full FAISS rebuild from DB");
 
-        let _: () = cmd("DEL")
-            .arg("faiss:next_id")
-            .query_async(&mut conn)
-            .await?;
+    match build_shadow(app_state, faiss, dimension, job_id).await {
+        Ok(shadow) => {
+            let count = shadow.ids.len();
+            faiss.finish_rebuild(shadow).await;
+            info!("🔀 Swapped in rebuilt FAISS index with {} jobs", count);
+        }
+        Err(e) => {
+            faiss.abort_rebuild().await;
+            return Err(e);
+        }
     }
 
-    let fresh_index: IndexImpl = index_factory(dimension, "IDMap,Flat", MetricType::InnerProduct)?;
+    save_faiss(faiss).await?;
 
-    {
-        let faiss = app_state.faiss.read().await;
-        let mut index = faiss.index.lock().await;
-        *index = fresh_index;
+    info!("FAISS rebuild completed successfully");
+    Ok(())
+}
+
+async fn build_shadow(
+    app_state: &Arc<AppState>,
+    faiss: &FaissService,
+    dimension: u32,
+    job_id: Option<&str>,
+) -> Result<IndexState, Box<dyn std::error::Error + Send + Sync>> {
+    let mut fresh_index: IndexImpl = faiss.empty_index()?;
+
+    if !fresh_index.is_trained() {
+        let limit = app_state.config.vector.training_sample.max(1);
+        let training = sample_training_vectors(app_state, dimension, limit).await?;
+        let count = training.len() / dimension as usize;
+
+        if count == 0 {
+            return Err("No embeddings available to train the FAISS index".into());
+        }
+
+        info!("🏋️ Training FAISS index on {} vectors", count);
+        fresh_index =
+            tokio::task::spawn_blocking(move || fresh_index.train(&training).map(|_| fresh_index))
+                .await??;
     }
 
-    let mut rows = stream_active_jobs_with_embeddings(&app_state.db_pool);
+    let mut shadow = IndexState::new(fresh_index, IdMap::default(), faiss.spec.supports_removal());
 
-    {
-        let faiss = app_state.faiss.read().await;
-        let mut count: u64 = 0;
+    let mut rows = stream_active_jobs_with_embeddings(
+        &app_state.db_pool,
+        app_state.embedding.model(),
+        dimension as i32,
+    );
 
-        while let Some(job) = rows.try_next().await? {
-            if let Err(e) = faiss.upsert(job.id, job.embedding).await {
-                error!("Failed to reindex job_id={}: {}", job.id, e);
-                continue;
-            }
-            count += 1;
+    let mut count: u64 = 0;
+
+    while let Some(job) = rows.try_next().await? {
+        let mut embedding = job.embedding;
+        normalize(&mut embedding);
+
+        if let Err(e) = shadow.upsert(job.id, &embedding) {
+            error!("Failed to reindex job_id={}: {}", job.id, e);
+            continue;
         }
+        count += 1;
 
-        info!("Rebuilt FAISS with {} jobs", count);
+        if let Some(job_id) = job_id {
+            if count % PROGRESS_EVERY == 0 {
+                record_indexed(app_state, job_id, count).await;
+            }
+        }
+    }
 
-        save_faiss(&faiss).await?;
+    if let Some(job_id) = job_id {
+        record_indexed(app_state, job_id, count).await;
     }
 
-    info!("FAISS rebuild completed successfully");
-    Ok(())
+    info!("Rebuilt FAISS with {} jobs", count);
+
+    Ok(shadow)
 }
@@ -1,2 +1,3 @@
+pub mod consistency;
 pub mod faiss_service;
 pub mod index_store;
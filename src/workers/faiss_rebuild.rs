@@ -0,0 +1,132 @@
+use deadpool_redis::redis::cmd;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::db::job::count_embeddings_by_model;
+use crate::state::AppState;
+use crate::vector::index_store::rebuild_claimed;
+
+pub const PROGRESS_EVERY: u64 = 1000;
+const PROGRESS_TTL_SECS: u64 = 86_400;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+#[derive(Debug)]
+pub struct RebuildInProgress;
+
+impl std::fmt::Display for RebuildInProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FAISS rebuild already in progress")
+    }
+}
+
+impl std::error::Error for RebuildInProgress {}
+
+fn progress_key(job_id: &str) -> String {
+    format!("faiss_rebuild:{}", job_id)
+}
+
+/// Claims the rebuild flag and queues a background shadow rebuild, returning
+/// its job id for polling. Fails with `RebuildInProgress` if one is running.
+pub async fn spawn(app_state: Arc<AppState>) -> Result<String, BoxError> {
+    // Claimed before answering, so concurrent requests can't both get a job id.
+    let faiss = app_state.faiss.read().await.clone();
+    if faiss.begin_rebuild().await.is_err() {
+        return Err(Box::new(RebuildInProgress));
+    }
+
+    let job_id = Uuid::new_v4().to_string();
+    if let Err(e) = start_progress(&app_state, &job_id).await {
+        faiss.abort_rebuild().await;
+        return Err(e);
+    }
+
+    let id = job_id.clone();
+    tokio::spawn(async move {
+        let dimension = app_state.embedding.dimension() as u32;
+
+        let mut fields = match rebuild_claimed(&app_state, &faiss, dimension, Some(&id)).await {
+            Ok(()) => {
+                info!("✅ FAISS rebuild job {} completed", id);
+                vec![("state", "completed".to_string())]
+            }
+            Err(e) => {
+                error!("❌ FAISS rebuild job {} failed: {}", id, e);
+                vec![("state", "failed".to_string()), ("error", e.to_string())]
+            }
+        };
+
+        fields.push(("finished_at", chrono::Utc::now().to_rfc3339()));
+        if let Err(e) = set_progress(&app_state, &id, &fields).await {
+            error!("Failed to record FAISS rebuild result: {}", e);
+        }
+    });
+
+    Ok(job_id)
+}
+
+async fn start_progress(app_state: &AppState, job_id: &str) -> Result<(), BoxError> {
+    let total = count_embeddings_by_model(
+        &app_state.db_pool,
+        app_state.embedding.model(),
+        app_state.embedding.dimension() as i32,
+        &app_state.match_rules.current().embedding_inputs_hash(),
+    )
+    .await?
+    .current;
+
+    set_progress(
+        app_state,
+        job_id,
+        &[
+            ("state", "running".to_string()),
+            ("total", total.to_string()),
+            ("indexed", "0".to_string()),
+            ("started_at", chrono::Utc::now().to_rfc3339()),
+        ],
+    )
+    .await
+}
+
+pub async fn record_indexed(app_state: &AppState, job_id: &str, indexed: u64) {
+    if let Err(e) = set_progress(app_state, job_id, &[("indexed", indexed.to_string())]).await {
+        error!("Failed to record FAISS rebuild progress: {}", e);
+    }
+}
+
+pub async fn get_progress(
+    app_state: &AppState,
+    job_id: &str,
+) -> Result<HashMap<String, String>, BoxError> {
+    let mut conn = app_state.redis_pool.get().await?;
+    let progress: HashMap<String, String> = cmd("HGETALL")
+        .arg(progress_key(job_id))
+        .query_async(&mut conn)
+        .await?;
+    Ok(progress)
+}
+
+async fn set_progress(
+    app_state: &AppState,
+    job_id: &str,
+    fields: &[(&str, String)],
+) -> Result<(), BoxError> {
+    let key = progress_key(job_id);
+    let mut conn = app_state.redis_pool.get().await?;
+
+    let mut hset = cmd("HSET");
+    hset.arg(&key);
+    for (field, value) in fields {
+        hset.arg(*field).arg(value);
+    }
+    let _: () = hset.query_async(&mut conn).await?;
+
+    let _: () = cmd("EXPIRE")
+        .arg(&key)
+        .arg(PROGRESS_TTL_SECS)
+        .query_async(&mut conn)
+        .await?;
+    Ok(())
+}
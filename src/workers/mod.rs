@@ -1,2 +1,5 @@
+pub mod faiss_rebuild;
 pub mod processor;
 pub mod redis_event_worker;
+pub mod reembed;
+pub mod response_listener;
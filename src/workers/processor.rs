@@ -12,19 +12,16 @@ pub fn spawn_processing_task(
     action: String,
     state: Arc<AppState>,
 ) {
-    task::spawn({
-        let config = state.config.clone();
-        async move {
-            match generate_response(&action, context, message, &state).await {
-                Ok(response) => {
-                    if let Err(e) = send_to_bpp_caller(&action, response, config).await {
-                        error!("Error sending to BPP client: {:?}", e);
-                    }
-                }
-                Err(e) => {
-                    error!("Error generating response: {:?}", e);
+    task::spawn(async move {
+        match generate_response(&action, context, message, &state).await {
+            Ok(response) => {
+                if let Err(e) = send_to_bpp_caller(&action, response, &state).await {
+                    error!("Error sending to BPP client: {:?}", e);
                 }
             }
+            Err(e) => {
+                error!("Error generating response: {:?}", e);
+            }
         }
     });
 }
@@ -0,0 +1,304 @@
+use deadpool_redis::redis::cmd;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::db::job::{
+    adopt_unhashed_job_embeddings, adopt_unversioned_embeddings, batch_update_job_embeddings,
+    count_embeddings_by_model, fetch_jobs_with_stale_embeddings, fetch_stale_embedding_models,
+};
+use crate::db::profiles::adopt_unhashed_profile_embeddings;
+use crate::state::AppState;
+use crate::utils::empeding::job_text_for_embedding;
+use crate::utils::profiles::update_profile_embeddings;
+use crate::utils::redis::delete_keys_by_pattern;
+use crate::vector::index_store::rebuild_faiss_from_db;
+
+pub const PROGRESS_KEY: &str = "reembed:progress";
+const LOCK_KEY: &str = "reembed:lock";
+const LOCK_TTL_SECS: u64 = 300;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Brings stored vectors in line with the configured embedding model and
+/// dimension, and with the embed fields of the current match rules. Vectors
+/// from older rules stay searchable until their replacement is written.
+///
+/// Runs at startup and after a rules reload that changes the embed text.
+/// Progress lives in the `reembed:progress` hash, so a restart resumes after
+/// the last job id written for the same target.
+pub async fn start(app_state: Arc<AppState>) {
+    loop {
+        let rules_hash = app_state.match_rules.current().embedding_inputs_hash();
+
+        if let Err(e) = run(&app_state).await {
+            error!("❌ Re-embedding failed: {}", e);
+            let _ = set_progress(&app_state, &[("state", "failed".to_string())]).await;
+            return;
+        }
+
+        // A reload during the run leaves the rows embedded before it stale again,
+        // and the run it triggered backed off because this one held the lock.
+        if app_state.match_rules.current().embedding_inputs_hash() == rules_hash {
+            return;
+        }
+    }
+}
+
+async fn run(app_state: &Arc<AppState>) -> Result<(), BoxError> {
+    let model = app_state.embedding.model().to_string();
+    let dimension = app_state.embedding.dimension() as i32;
+    let rules_hash = app_state.match_rules.current().embedding_inputs_hash();
+    // The rules hash keeps a run for older rules from being resumed.
+    let target = format!("{}:{}:{}", model, dimension, &rules_hash[..12]);
+
+    // Vectors from before rules hashing were built from the rules live now.
+    let jobs = adopt_unhashed_job_embeddings(&app_state.db_pool, &rules_hash).await?;
+    let profiles = adopt_unhashed_profile_embeddings(&app_state.db_pool, &rules_hash).await?;
+    if jobs + profiles > 0 {
+        info!(
+            "🏷️ Tagged {} job and {} profile embeddings with the current embed rules",
+            jobs, profiles
+        );
+    }
+
+    // Without a configured legacy model the untagged vectors count as stale.
+    if let Some(legacy_model) = &app_state.config.embedding.legacy_model {
+        let adopted = adopt_unversioned_embeddings(&app_state.db_pool, legacy_model).await?;
+        if adopted > 0 {
+            info!(
+                "🏷️ Tagged {} unversioned job embeddings as {}",
+                adopted, legacy_model
+            );
+        }
+    }
+
+    let counts =
+        count_embeddings_by_model(&app_state.db_pool, &model, dimension, &rules_hash).await?;
+    let faiss_empty = app_state.faiss.read().await.ntotal().await == 0;
+
+    if counts.stale == 0 {
+        if faiss_empty && counts.current > 0 {
+            info!("🔄 FAISS index is empty, rebuilding from stored embeddings");
+            rebuild_faiss_from_db(app_state, dimension as u32, None).await?;
+        }
+        return Ok(());
+    }
+
+    info!(
+        "⚠️ {} job embeddings do not match {}, starting re-embedding",
+        counts.stale, target
+    );
+
+    if !acquire_lock(app_state).await? {
+        info!("⏭️ Re-embedding already running on another instance");
+        return Ok(());
+    }
+
+    let result = reembed(
+        app_state,
+        &model,
+        dimension,
+        &rules_hash,
+        &target,
+        counts.stale,
+    )
+    .await;
+
+    // Release on failure too, so a restart doesn't wait out the lock TTL.
+    if let Err(e) = release_lock(app_state).await {
+        error!("❌ Failed to release re-embedding lock: {}", e);
+    }
+
+    result?;
+    info!("✅ Re-embedding to {} completed", target);
+    Ok(())
+}
+
+async fn reembed(
+    app_state: &Arc<AppState>,
+    model: &str,
+    dimension: i32,
+    rules_hash: &str,
+    target: &str,
+    stale: i64,
+) -> Result<(), BoxError> {
+    let progress = get_progress(app_state).await?;
+    let resuming = progress.get("target").map(String::as_str) == Some(target)
+        && progress.get("state").map(String::as_str) == Some("running");
+
+    let mut cursor: Option<Uuid> = None;
+    if resuming {
+        cursor = progress.get("cursor").and_then(|c| Uuid::parse_str(c).ok());
+        info!("▶️ Resuming re-embedding to {} after {:?}", target, cursor);
+    } else {
+        let mut conn = app_state.redis_pool.get().await?;
+        let _: () = cmd("DEL").arg(PROGRESS_KEY).query_async(&mut conn).await?;
+        set_progress(
+            app_state,
+            &[
+                ("target", target.to_string()),
+                ("state", "running".to_string()),
+                ("total", stale.to_string()),
+                ("done", "0".to_string()),
+                ("failed", "0".to_string()),
+                ("started_at", chrono::Utc::now().to_rfc3339()),
+            ],
+        )
+        .await?;
+    }
+
+    let old_models = fetch_stale_embedding_models(&app_state.db_pool, model).await?;
+
+    let options = app_state.embedding.batch_options();
+    let page_size = (options.batch_size * options.concurrency).max(1) as i64;
+
+    loop {
+        let jobs = fetch_jobs_with_stale_embeddings(
+            &app_state.db_pool,
+            model,
+            dimension,
+            rules_hash,
+            cursor,
+            page_size,
+        )
+        .await?;
+
+        let Some(last) = jobs.last() else {
+            break;
+        };
+        cursor = Some(last.id);
+
+        let rules = app_state.match_rules.current();
+        let texts: Vec<String> = jobs
+            .iter()
+            .map(|job| {
+                job.beckn_structure
+                    .as_ref()
+                    .map(|b| job_text_for_embedding(b, &rules))
+                    .unwrap_or_default()
+            })
+            .collect();
+        let text_refs: Vec<&str> = texts.iter().map(String::as_str).collect();
+
+        let embeddings = {
+            let mut conn = app_state.redis_pool.get().await?;
+            app_state
+                .embedding
+                .get_embeddings(&text_refs, &mut conn)
+                .await
+        };
+
+        let updates: Vec<(Uuid, Vec<f32>)> = jobs
+            .iter()
+            .zip(embeddings)
+            .filter_map(|(job, emb)| Some((job.id, emb?)))
+            .collect();
+        let failed = jobs.len() - updates.len();
+
+        batch_update_job_embeddings(
+            &app_state.db_pool,
+            &updates,
+            model,
+            dimension,
+            &rules.embedding_inputs_hash(),
+        )
+        .await?;
+
+        let mut conn = app_state.redis_pool.get().await?;
+        let _: () = cmd("HSET")
+            .arg(PROGRESS_KEY)
+            .arg("cursor")
+            .arg(last.id.to_string())
+            .query_async(&mut conn)
+            .await?;
+        let _: i64 = cmd("HINCRBY")
+            .arg(PROGRESS_KEY)
+            .arg("done")
+            .arg(updates.len())
+            .query_async(&mut conn)
+            .await?;
+        let _: i64 = cmd("HINCRBY")
+            .arg(PROGRESS_KEY)
+            .arg("failed")
+            .arg(failed)
+            .query_async(&mut conn)
+            .await?;
+        let _: () = cmd("EXPIRE")
+            .arg(LOCK_KEY)
+            .arg(LOCK_TTL_SECS)
+            .query_async(&mut conn)
+            .await?;
+
+        info!(
+            "🔁 Re-embedded {} jobs ({} failed), cursor {}",
+            updates.len(),
+            failed,
+            last.id
+        );
+    }
+
+    let profiles = update_profile_embeddings(app_state).await;
+    info!("🔁 Re-embedded {} profiles", profiles);
+
+    {
+        let mut conn = app_state.redis_pool.get().await?;
+        for old in old_models {
+            delete_keys_by_pattern(&mut conn, &format!("embedding:{}:*", old)).await?;
+            info!("🧹 Dropped cached vectors for model {}", old);
+        }
+    }
+
+    rebuild_faiss_from_db(app_state, dimension as u32, None).await?;
+
+    set_progress(
+        app_state,
+        &[
+            ("state", "completed".to_string()),
+            ("finished_at", chrono::Utc::now().to_rfc3339()),
+        ],
+    )
+    .await?;
+
+    Ok(())
+}
+
+async fn acquire_lock(app_state: &AppState) -> Result<bool, BoxError> {
+    let mut conn = app_state.redis_pool.get().await?;
+    let acquired: Option<String> = cmd("SET")
+        .arg(LOCK_KEY)
+        .arg("1")
+        .arg("NX")
+        .arg("EX")
+        .arg(LOCK_TTL_SECS)
+        .query_async(&mut conn)
+        .await?;
+    Ok(acquired.is_some())
+}
+
+async fn release_lock(app_state: &AppState) -> Result<(), BoxError> {
+    let mut conn = app_state.redis_pool.get().await?;
+    let _: () = cmd("DEL").arg(LOCK_KEY).query_async(&mut conn).await?;
+    Ok(())
+}
+
+pub async fn get_progress(app_state: &AppState) -> Result<HashMap<String, String>, BoxError> {
+    let mut conn = app_state.redis_pool.get().await?;
+    let progress: HashMap<String, String> = cmd("HGETALL")
+        .arg(PROGRESS_KEY)
+        .query_async(&mut conn)
+        .await?;
+    Ok(progress)
+}
+
+async fn set_progress(app_state: &AppState, fields: &[(&str, String)]) -> Result<(), BoxError> {
+    let mut conn = app_state.redis_pool.get().await?;
+    let mut hset = cmd("HSET");
+    hset.arg(PROGRESS_KEY);
+    for (field, value) in fields {
+        hset.arg(*field).arg(value);
+    }
+    let _: () = hset.query_async(&mut conn).await?;
+    Ok(())
+}
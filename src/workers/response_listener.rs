@@ -0,0 +1,67 @@
+use futures::StreamExt;
+use redis::RedisResult;
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+use tracing::{error, info};
+
+use crate::state::AppState;
+use crate::utils::correlation::RESPONSE_CHANNEL_PREFIX;
+
+pub async fn start(state: Arc<AppState>) {
+    info!("Starting Redis response listener...");
+
+    loop {
+        if let Err(e) = run_listener(&state).await {
+            error!("Redis response listener crashed: {:?}", e);
+        }
+        sleep(Duration::from_secs(3)).await;
+    }
+}
+
+async fn run_listener(state: &Arc<AppState>) -> RedisResult<()> {
+    let mut pubsub = state.redis_client.get_async_pubsub().await?;
+    pubsub
+        .psubscribe(format!("{}*", RESPONSE_CHANNEL_PREFIX))
+        .await?;
+
+    info!(
+        "Redis response listener subscribed to '{}*'",
+        RESPONSE_CHANNEL_PREFIX
+    );
+
+    let mut messages = pubsub.on_message();
+
+    while let Some(msg) = messages.next().await {
+        let key = match msg.get_channel_name().strip_prefix(RESPONSE_CHANNEL_PREFIX) {
+            Some(k) => k.to_string(),
+            None => continue,
+        };
+
+        // Only the instance holding the waiter has an entry for this key.
+        let Some((_, sender)) = state.shared_state.pending_searches.remove(&key) else {
+            continue;
+        };
+
+        let payload: String = match msg.get_payload() {
+            Ok(p) => p,
+            Err(e) => {
+                error!("❌ Failed to read response payload for {}: {:?}", key, e);
+                continue;
+            }
+        };
+
+        match serde_json::from_str::<Value>(&payload) {
+            Ok(json_value) => {
+                if let Err(e) = sender.send(json_value) {
+                    info!("⚠️ Failed to deliver response for {}: {:?}", key, e);
+                } else {
+                    info!("✅ Delivered response to waiting request {}", key);
+                }
+            }
+            Err(e) => error!("❌ Failed to parse response payload for {}: {:?}", key, e),
+        }
+    }
+
+    Ok(())
+}